@@ -0,0 +1,260 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::{stream, Stream, StreamExt};
+use log::{error, warn};
+
+use crate::data_structures::{interval::Interval, kline::Kline};
+
+use super::{Binance, Result};
+
+type KlineStream = Pin<Box<dyn Stream<Item = Result<Kline>> + Send>>;
+
+// Shared by the clones of a `Binance` source: the last bar of the REST history,
+// where the live stream has to continue from, and the gaps backfilled so far.
+#[derive(Debug, Default)]
+pub(super) struct Continuity {
+    last_history_bar: Mutex<Option<(DateTime<Utc>, bool)>>,
+    gaps: AtomicUsize,
+}
+
+impl Continuity {
+    pub(super) fn record_history(&self, klines: &[Kline]) {
+        let Some(kline) = klines.last() else {
+            return;
+        };
+
+        if let Ok(mut last) = self.last_history_bar.lock() {
+            if last.is_none_or(|(time, _)| kline.time >= time) {
+                *last = Some((kline.time, kline.closed));
+            }
+        }
+    }
+
+    fn last_history_bar(&self) -> Option<(DateTime<Utc>, bool)> {
+        self.last_history_bar.lock().ok().and_then(|last| *last)
+    }
+}
+
+// Watches the live stream for bars that were missed while the websocket was
+// disconnected and fetches them through the REST API before the bar that
+// revealed the gap is emitted.
+struct Backfill {
+    binance: Binance,
+    interval: Interval,
+    last: Option<(DateTime<Utc>, bool)>,
+}
+
+impl Backfill {
+    async fn process(&mut self, kline: Kline) -> Vec<Result<Kline>> {
+        let mut items = Vec::new();
+
+        if let Some((last_time, last_closed)) = self.last {
+            let gap_start = if last_closed {
+                self.interval.next(last_time)
+            } else {
+                last_time
+            };
+
+            if kline.time > gap_start {
+                items.extend(self.fill(gap_start, kline.time).await);
+            }
+
+            if kline.time < last_time {
+                return items;
+            }
+        }
+
+        self.last = Some((kline.time, kline.closed));
+        items.push(Ok(kline));
+        items
+    }
+
+    async fn fill(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<Result<Kline>> {
+        let mut missing = 0;
+        let mut time = start;
+        while time < end {
+            missing += 1;
+            time = self.interval.next(time);
+        }
+
+        self.binance.continuity.gaps.fetch_add(1, Ordering::Relaxed);
+        warn!(
+            "gap of {} {} bars in {} stream from {} to {}, backfilling",
+            missing, self.interval, self.binance.symbol, start, end
+        );
+
+        match self
+            .binance
+            .fetch_klines_range(start, end - TimeDelta::milliseconds(1))
+            .await
+        {
+            Ok(klines) => {
+                if klines.len() < missing {
+                    warn!("backfilled {} of {} missing bars", klines.len(), missing);
+                }
+
+                if let Some(kline) = klines.last() {
+                    self.last = Some((kline.time, kline.closed));
+                }
+
+                klines.into_iter().map(Ok).collect()
+            }
+            Err(e) => {
+                error!("failed to backfill gap: {:?}", e);
+                vec![Err(e)]
+            }
+        }
+    }
+}
+
+impl Binance {
+    // Number of gaps found in the live stream and backfilled through REST.
+    pub fn gaps(&self) -> usize {
+        self.continuity.gaps.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn backfilled<S>(&self, live: S) -> KlineStream
+    where
+        S: Stream<Item = Result<Kline>> + Send + 'static,
+    {
+        let interval = match self.interval.parse::<Interval>() {
+            Ok(interval) => interval,
+            Err(e) => {
+                warn!("{}, gaps in the live stream will not be backfilled", e);
+                return Box::pin(live);
+            }
+        };
+
+        let backfill = Backfill {
+            binance: self.clone(),
+            interval,
+            // the first live bar is checked against the end of the history
+            last: self.continuity.last_history_bar(),
+        };
+        let live: KlineStream = Box::pin(live);
+
+        Box::pin(stream::unfold(
+            (live, backfill, VecDeque::new()),
+            |(mut live, mut backfill, mut pending)| async move {
+                loop {
+                    if let Some(item) = pending.pop_front() {
+                        return Some((item, (live, backfill, pending)));
+                    }
+
+                    match live.next().await? {
+                        Ok(kline) => pending.extend(backfill.process(kline).await),
+                        Err(e) => return Some((Err(e), (live, backfill, pending))),
+                    }
+                }
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use futures::StreamExt;
+
+    use crate::connectors::binance::{
+        test_server::{kline_event, klines_page, query_param, serve_http, serve_ws, HttpResponse},
+        Binance,
+    };
+    use crate::source::Source;
+
+    const MINUTE_MS: i64 = 60_000;
+    const FIRST: i64 = 1_700_000_040_000;
+
+    // The REST history holds the first two bars, ranges cover the first ten.
+    async fn binance(events: Vec<String>) -> Binance {
+        let rest_url = serve_http(|path| {
+            let start: i64 = query_param(path, "startTime").map_or(FIRST, |v| v.parse().unwrap());
+            let end: i64 = query_param(path, "endTime").map_or(FIRST + MINUTE_MS, |v| v.parse().unwrap());
+            let times = (0..10)
+                .map(|i| FIRST + i * MINUTE_MS)
+                .filter(|t| *t >= start && *t <= end)
+                .collect::<Vec<i64>>();
+
+            HttpResponse::ok(klines_page(&times))
+        })
+        .await;
+        let server = serve_ws(events).await;
+
+        Binance::new("BTCUSDT".to_string(), "1m".to_string())
+            .await
+            .with_rest_url(rest_url)
+            .with_stream_url(server.url.clone())
+    }
+
+    fn bar(index: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp_millis(FIRST + index * MINUTE_MS).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_missing_bars_are_backfilled_in_order() {
+        let binance = binance(vec![
+            kline_event("BTCUSDT", "1m", FIRST, 1.0, true),
+            kline_event("BTCUSDT", "1m", FIRST + 3 * MINUTE_MS, 1.0, false),
+            kline_event("BTCUSDT", "1m", FIRST + 3 * MINUTE_MS, 1.0, true),
+        ])
+        .await;
+
+        let klines = binance
+            .fetch_live()
+            .take(5)
+            .map(|k| k.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        let times = klines.iter().map(|k| k.time).collect::<Vec<_>>();
+        assert_eq!(times, vec![bar(0), bar(1), bar(2), bar(3), bar(3)]);
+        assert!(klines[1].closed && klines[2].closed);
+        assert!(!klines[3].closed && klines[4].closed);
+        assert_eq!(binance.gaps(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unfinished_bar_is_completed_from_rest() {
+        let binance = binance(vec![
+            kline_event("BTCUSDT", "1m", FIRST, 1.0, false),
+            kline_event("BTCUSDT", "1m", FIRST + MINUTE_MS, 1.0, true),
+        ])
+        .await;
+
+        let klines = binance
+            .fetch_live()
+            .take(3)
+            .map(|k| k.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        let times = klines.iter().map(|k| (k.time, k.closed)).collect::<Vec<_>>();
+        assert_eq!(times, vec![(bar(0), false), (bar(0), true), (bar(1), true)]);
+    }
+
+    #[tokio::test]
+    async fn test_gap_after_history_is_backfilled() {
+        let binance = binance(vec![kline_event("BTCUSDT", "1m", FIRST + 4 * MINUTE_MS, 1.0, true)]).await;
+
+        let history = binance.fetch_history().await.unwrap();
+        let klines = binance
+            .fetch_live()
+            .take(3)
+            .map(|k| k.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(history.last().unwrap().time, bar(1));
+        let times = klines.iter().map(|k| k.time).collect::<Vec<_>>();
+        assert_eq!(times, vec![bar(2), bar(3), bar(4)]);
+        assert_eq!(binance.gaps(), 1);
+    }
+}
@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use chrono::{DateTime, Utc};
-use log::trace;
-use reqwest::Client;
+use log::{trace, warn};
+use reqwest::{header::HeaderMap, Client, StatusCode};
 use serde::{de::IgnoredAny, Deserialize};
 
 use crate::data_structures::kline::Kline;
@@ -8,6 +10,14 @@ use crate::data_structures::kline::Kline;
 use super::Binance;
 use super::Result;
 
+const KLINES_PAGE_LIMIT: usize = 1000;
+
+// Binance answers every request with the weight used in the current minute. We
+// pause before reaching the limit of the market instead of waiting for a 429.
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-1m";
+const RATE_LIMIT_WAIT: u64 = 60;
+const RATE_LIMIT_RETRIES: usize = 5;
+
 #[derive(Deserialize, Debug)]
 struct BinanceHistoryKLine(
     i64,        // Open time
@@ -16,7 +26,7 @@ struct BinanceHistoryKLine(
     String,     // Low price
     String,     // Close price
     String,     // Volume
-    IgnoredAny, // i64,    // Close time
+    i64,        // Close time
     IgnoredAny, // String, // Quote asset volume
     IgnoredAny, // i64,    // Number of trades
     IgnoredAny, // String, // Taker buy base asset volume
@@ -34,6 +44,7 @@ impl From<BinanceHistoryKLine> for Kline {
             low: kline.3.parse().expect("failed to parce low price"),
             close: kline.4.parse().expect("failed to parce close price"),
             volume: kline.5.parse().expect("failed to parce volume"),
+            closed: kline.6 < Utc::now().timestamp_millis(),
         }
     }
 }
@@ -41,37 +52,304 @@ impl From<BinanceHistoryKLine> for Kline {
 impl Binance {
     pub async fn fetch_historical_klines(&self) -> Result<Vec<Kline>> {
         let client = Client::new();
-        let url = format!(
-            "https://api.binance.com/api/v3/klines?symbol={}&interval={}",
-            self.symbol, self.interval
-        );
-
-        let response = client.get(&url).send().await?;
-        let status = response.status();
-
-        if !status.is_success() {
-            let error_text = response.text().await?;
-            let error = format!("API error: status: {}, message: {}", status, error_text);
-            return Err(error.into());
-        }
+        let params = [("symbol", self.symbol.clone()), ("interval", self.interval.clone())];
+
+        let klines = self.fetch_klines_page(&client, &params).await?;
 
-        let raw_response = response.text().await?;
-        let binance_klines: Vec<BinanceHistoryKLine> = match serde_json::from_str(&raw_response) {
-            Ok(klines) => klines,
-            Err(e) => return Err(Box::new(e)),
-        };
+        trace!("Loaded {} historical klines", klines.len());
+
+        Ok(klines)
+    }
+
+    pub async fn fetch_klines_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<Kline>> {
+        let client = Client::new();
+        let end_time = end.timestamp_millis();
+        let mut cursor = start.timestamp_millis();
+        let mut klines: Vec<Kline> = Vec::new();
 
-        trace!("Loaded {} historical klines", binance_klines.len());
+        while cursor <= end_time {
+            let params = [
+                ("symbol", self.symbol.clone()),
+                ("interval", self.interval.clone()),
+                ("startTime", cursor.to_string()),
+                ("endTime", end_time.to_string()),
+                ("limit", KLINES_PAGE_LIMIT.to_string()),
+            ];
 
-        let klines = binance_klines
-            .into_iter()
-            .map(|k| {
-                let mut kline = Kline::from(k);
-                kline.symbol = self.symbol.clone();
-                kline
-            })
-            .collect();
+            let page = self.fetch_klines_page(&client, &params).await?;
+            let page_len = page.len();
+            let page_end = match page.last() {
+                Some(kline) => kline.time.timestamp_millis(),
+                None => break,
+            };
+
+            for kline in page {
+                let time = kline.time.timestamp_millis();
+                let is_new = klines.last().is_none_or(|last| kline.time > last.time);
+
+                if is_new && time >= cursor && time <= end_time {
+                    klines.push(kline);
+                }
+            }
+
+            trace!("Loaded page of {} klines, {} in total", page_len, klines.len());
+
+            if page_len < KLINES_PAGE_LIMIT {
+                break;
+            }
+
+            cursor = i64::max(page_end, cursor) + 1;
+        }
 
         Ok(klines)
     }
+
+    async fn fetch_klines_page(&self, client: &Client, params: &[(&str, String)]) -> Result<Vec<Kline>> {
+        let url = self.klines_url();
+
+        let mut retries = 0;
+
+        loop {
+            let response = client.get(&url).query(params).send().await?;
+            let status = response.status();
+
+            // 418 means the IP got banned for ignoring 429s, retrying only
+            // extends the ban
+            if status == StatusCode::IM_A_TEAPOT {
+                return Err(format!("IP banned by binance (status: {})", status).into());
+            }
+
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                if retries == RATE_LIMIT_RETRIES {
+                    return Err(format!("still rate limited by binance after {} retries", retries).into());
+                }
+
+                let wait = Self::retry_after(response.headers()).unwrap_or(RATE_LIMIT_WAIT);
+                warn!("rate limited by binance (status: {}), retrying in {}s", status, wait);
+                tokio::time::sleep(Duration::from_secs(wait)).await;
+                retries += 1;
+                continue;
+            }
+
+            if !status.is_success() {
+                let error_text = response.text().await?;
+                let error = format!("API error: status: {}, message: {}", status, error_text);
+                return Err(error.into());
+            }
+
+            if let Some(used_weight) = Self::used_weight(response.headers()) {
+                if self.market.weight_exhausted(used_weight) {
+                    let wait = 60 - (Utc::now().timestamp() % 60) as u64;
+                    warn!("request weight {} reached, pausing for {}s", used_weight, wait);
+                    tokio::time::sleep(Duration::from_secs(wait)).await;
+                }
+            }
+
+            let raw_response = response.text().await?;
+            let binance_klines: Vec<BinanceHistoryKLine> = match serde_json::from_str(&raw_response) {
+                Ok(klines) => klines,
+                Err(e) => return Err(Box::new(e)),
+            };
+
+            let klines = binance_klines
+                .into_iter()
+                .map(|k| {
+                    let mut kline = Kline::from(k);
+                    kline.symbol = self.symbol.clone();
+                    kline
+                })
+                .collect();
+
+            return Ok(klines);
+        }
+    }
+
+    fn retry_after(headers: &HeaderMap) -> Option<u64> {
+        headers.get("retry-after")?.to_str().ok()?.parse().ok()
+    }
+
+    fn used_weight(headers: &HeaderMap) -> Option<u32> {
+        headers.get(USED_WEIGHT_HEADER)?.to_str().ok()?.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use chrono::{DateTime, TimeDelta, Utc};
+
+    use crate::connectors::binance::{
+        test_server::{klines_page, query_param, serve_http, HttpResponse},
+        Binance,
+    };
+
+    const MINUTE_MS: i64 = 60_000;
+
+    fn start_time() -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp_millis(1_700_000_000_000 / MINUTE_MS * MINUTE_MS).unwrap()
+    }
+
+    async fn binance(url: String) -> Binance {
+        Binance::new("BTCUSDT".to_string(), "1m".to_string())
+            .await
+            .with_rest_url(url)
+    }
+
+    // Serves `total` one minute klines starting at `first`, honouring startTime,
+    // endTime and limit. With `overlap` every page also repeats the previous bar.
+    fn exchange(first: i64, total: i64, overlap: bool) -> impl Fn(&str) -> HttpResponse {
+        move |path| {
+            let start = query_param(path, "startTime").map_or(first, |v| v.parse().unwrap());
+            let end = query_param(path, "endTime").map_or(i64::MAX, |v| v.parse().unwrap());
+            let limit = query_param(path, "limit").map_or(500, |v| v.parse().unwrap());
+            let last = first + (total - 1) * MINUTE_MS;
+
+            let mut from = (start - first + MINUTE_MS - 1).div_euclid(MINUTE_MS) * MINUTE_MS + first;
+            if overlap && from > first {
+                from -= MINUTE_MS;
+            }
+
+            let times = (0..limit)
+                .map(|i| from + i * MINUTE_MS)
+                .filter(|t| *t >= first && *t <= end.min(last))
+                .collect::<Vec<i64>>();
+
+            HttpResponse::ok(klines_page(&times))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_range_walks_pages() {
+        let first = start_time().timestamp_millis();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let handler = exchange(first, 2500, false);
+        let url = serve_http(move |path| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            handler(path)
+        })
+        .await;
+
+        let start = start_time();
+        let end = start + TimeDelta::minutes(2499);
+        let klines = binance(url).await.fetch_klines_range(start, end).await.unwrap();
+
+        assert_eq!(klines.len(), 2500);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert_eq!(klines[0].time, start);
+        assert_eq!(klines[2499].time, end);
+        assert!(klines
+            .windows(2)
+            .all(|w| w[1].time - w[0].time == TimeDelta::minutes(1)));
+        assert!(klines.iter().all(|k| k.symbol == "BTCUSDT"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_range_removes_duplicated_page_boundaries() {
+        let first = start_time().timestamp_millis();
+        let url = serve_http(exchange(first, 2200, true)).await;
+
+        let start = start_time();
+        let end = start + TimeDelta::minutes(2199);
+        let klines = binance(url).await.fetch_klines_range(start, end).await.unwrap();
+
+        assert_eq!(klines.len(), 2200);
+        assert!(klines
+            .windows(2)
+            .all(|w| w[1].time - w[0].time == TimeDelta::minutes(1)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_range_stops_at_end_time() {
+        let first = start_time().timestamp_millis();
+        let url = serve_http(exchange(first, 5000, false)).await;
+
+        let start = start_time() + TimeDelta::minutes(10);
+        let end = start + TimeDelta::minutes(99);
+        let klines = binance(url).await.fetch_klines_range(start, end).await.unwrap();
+
+        assert_eq!(klines.len(), 100);
+        assert_eq!(klines[0].time, start);
+        assert_eq!(klines[99].time, end);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_range_retries_when_rate_limited() {
+        let first = start_time().timestamp_millis();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let handler = exchange(first, 10, false);
+        let url = serve_http(move |path| match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => HttpResponse::new(429, "{}".to_string()).with_header("Retry-After", "0"),
+            _ => handler(path),
+        })
+        .await;
+
+        let start = start_time();
+        let klines = binance(url)
+            .await
+            .fetch_klines_range(start, start + TimeDelta::minutes(9))
+            .await
+            .unwrap();
+
+        assert_eq!(klines.len(), 10);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_range_gives_up_when_still_rate_limited() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let url = serve_http(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            HttpResponse::new(429, "{}".to_string()).with_header("Retry-After", "0")
+        })
+        .await;
+
+        let start = start_time();
+        let result = binance(url)
+            .await
+            .fetch_klines_range(start, start + TimeDelta::minutes(9))
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 6);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_range_does_not_retry_when_banned() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let url = serve_http(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            HttpResponse::new(418, "{}".to_string()).with_header("Retry-After", "120")
+        })
+        .await;
+
+        let start = start_time();
+        let result = binance(url)
+            .await
+            .fetch_klines_range(start, start + TimeDelta::minutes(9))
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_range_reports_api_errors() {
+        let url = serve_http(|_| HttpResponse::new(400, r#"{"code":-1121,"msg":"Invalid symbol."}"#.to_string())).await;
+
+        let start = start_time();
+        let result = binance(url)
+            .await
+            .fetch_klines_range(start, start + TimeDelta::minutes(9))
+            .await;
+
+        assert!(result.is_err());
+    }
 }
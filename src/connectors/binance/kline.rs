@@ -14,6 +14,7 @@ impl From<KlineEvent> for Kline {
             low: event.kline.low.parse().expect("failed to parse low price"),
             high: event.kline.high.parse().expect("failed to parse high price"),
             volume: event.kline.volume.parse().expect("failed to parse volume"),
+            closed: event.kline.closed,
         }
     }
 }
@@ -48,3 +48,25 @@ pub struct KlineDetail {
     #[serde(rename = "Q")]
     pub active_quote: String,
 }
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum CombinedMessage {
+    Event { stream: String, data: Box<KlineEvent> },
+    // listed before `Response`, which would match error replies as well
+    Error { error: ResponseError, id: u64 },
+    Response { id: u64 },
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseError {
+    pub code: i64,
+    pub msg: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct StreamRequest {
+    pub method: &'static str,
+    pub params: Vec<String>,
+    pub id: u64,
+}
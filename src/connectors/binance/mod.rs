@@ -1,24 +1,182 @@
+mod backfill;
 mod history;
 mod kline;
 mod message;
+pub mod multiplex;
 mod source;
 pub mod stream;
+#[cfg(test)]
+pub(crate) mod test_server;
 
-use std::error::Error;
+use std::{error::Error, sync::Arc};
+
+use url::Url;
+
+use backfill::Continuity;
 
 pub type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync + 'static>>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Market {
+    Spot,
+    UsdmFutures,
+}
+
+impl Market {
+    fn rest_url(&self) -> &'static str {
+        match self {
+            Market::Spot => "https://api.binance.com",
+            Market::UsdmFutures => "https://fapi.binance.com",
+        }
+    }
+
+    fn klines_path(&self) -> &'static str {
+        match self {
+            Market::Spot => "/api/v3/klines",
+            Market::UsdmFutures => "/fapi/v1/klines",
+        }
+    }
+
+    fn stream_url(&self) -> &'static str {
+        match self {
+            Market::Spot => "wss://stream.binance.com:9443",
+            Market::UsdmFutures => "wss://fstream.binance.com",
+        }
+    }
+
+    // Request weight the IP may use per minute.
+    fn weight_limit(&self) -> u32 {
+        match self {
+            Market::Spot => 6000,
+            Market::UsdmFutures => 2400,
+        }
+    }
+
+    // Weight of a klines request for a full page.
+    fn klines_weight(&self) -> u32 {
+        match self {
+            Market::Spot => 2,
+            Market::UsdmFutures => 5,
+        }
+    }
+
+    // Whether another klines page would exceed the weight limit of the minute.
+    fn weight_exhausted(&self, used_weight: u32) -> bool {
+        used_weight + self.klines_weight() > self.weight_limit()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Binance {
     name: String,
     symbol: String,
     interval: String,
+    market: Market,
+    rest_url: Option<String>,
+    stream_url: Option<String>,
+    continuity: Arc<Continuity>,
 }
 
 impl Binance {
     pub async fn new(symbol: String, interval: String) -> Self {
         let name = "binance".to_string();
 
-        Binance { name, symbol, interval }
+        Binance {
+            name,
+            symbol,
+            interval,
+            market: Market::Spot,
+            rest_url: None,
+            stream_url: None,
+            continuity: Arc::new(Continuity::default()),
+        }
+    }
+
+    pub fn with_market(mut self, market: Market) -> Self {
+        self.market = market;
+        self
+    }
+
+    pub fn with_rest_url(mut self, rest_url: String) -> Self {
+        self.rest_url = Some(rest_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn with_stream_url(mut self, stream_url: String) -> Self {
+        self.stream_url = Some(stream_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    fn klines_url(&self) -> String {
+        let rest_url = self.rest_url.as_deref().unwrap_or(self.market.rest_url());
+
+        format!("{}{}", rest_url, self.market.klines_path())
+    }
+
+    fn kline_stream_url(&self) -> Result<Url> {
+        let stream_url = self.stream_url.as_deref().unwrap_or(self.market.stream_url());
+        let url = format!(
+            "{}/ws/{}@kline_{}",
+            stream_url,
+            self.symbol.to_lowercase(),
+            self.interval
+        );
+
+        Ok(Url::parse(&url)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn binance(market: Market) -> Binance {
+        Binance::new("ETHUSDT".to_string(), "5m".to_string())
+            .await
+            .with_market(market)
+    }
+
+    #[tokio::test]
+    async fn test_spot_endpoints() {
+        let binance = binance(Market::Spot).await;
+
+        assert_eq!(binance.klines_url(), "https://api.binance.com/api/v3/klines");
+        assert_eq!(
+            binance.kline_stream_url().unwrap().as_str(),
+            "wss://stream.binance.com:9443/ws/ethusdt@kline_5m"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_futures_endpoints() {
+        let binance = binance(Market::UsdmFutures).await;
+
+        assert_eq!(binance.klines_url(), "https://fapi.binance.com/fapi/v1/klines");
+        assert_eq!(
+            binance.kline_stream_url().unwrap().as_str(),
+            "wss://fstream.binance.com/ws/ethusdt@kline_5m"
+        );
+    }
+
+    #[test]
+    fn test_weight_limits() {
+        assert!(!Market::Spot.weight_exhausted(5998));
+        assert!(Market::Spot.weight_exhausted(5999));
+        assert!(!Market::UsdmFutures.weight_exhausted(2395));
+        assert!(Market::UsdmFutures.weight_exhausted(2396));
+    }
+
+    #[tokio::test]
+    async fn test_custom_base_urls() {
+        let binance = binance(Market::UsdmFutures)
+            .await
+            .with_rest_url("http://127.0.0.1:8080/".to_string())
+            .with_stream_url("ws://127.0.0.1:9090".to_string());
+
+        assert_eq!(binance.klines_url(), "http://127.0.0.1:8080/fapi/v1/klines");
+        assert_eq!(
+            binance.kline_stream_url().unwrap().as_str(),
+            "ws://127.0.0.1:9090/ws/ethusdt@kline_5m"
+        );
     }
 }
@@ -0,0 +1,406 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use log::{error, trace, warn};
+use tokio::sync::mpsc;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{protocol::Message, Bytes},
+};
+use url::Url;
+
+use super::message::{CombinedMessage, KlineEvent, StreamRequest};
+use super::stream::{Stream, RECONNECT_WAIT};
+use super::{Market, Result};
+
+type Subscribers = HashMap<String, mpsc::Sender<Result<KlineEvent>>>;
+
+const SUBSCRIBER_BUFFER: usize = 100;
+
+enum Command {
+    Subscribe(String, mpsc::Sender<Result<KlineEvent>>),
+    Unsubscribe(String),
+}
+
+// A single websocket connection to the combined-stream endpoint shared by many
+// symbols. Subscriptions are added and removed at runtime with the
+// SUBSCRIBE/UNSUBSCRIBE requests, and restored on reconnect.
+pub struct Multiplex {
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl Multiplex {
+    pub fn new(base_url: Url) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            Self::manage_connection(base_url, rx).await;
+        });
+
+        Self { commands: tx }
+    }
+
+    pub fn for_market(market: Market) -> Result<Self> {
+        Ok(Self::new(Url::parse(market.stream_url())?))
+    }
+
+    pub fn subscribe(&self, symbol: &str, interval: &str) -> Stream {
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER);
+        let _ = self
+            .commands
+            .send(Command::Subscribe(Self::stream_name(symbol, interval), tx));
+
+        Stream::with_receiver(rx)
+    }
+
+    pub fn unsubscribe(&self, symbol: &str, interval: &str) {
+        let _ = self
+            .commands
+            .send(Command::Unsubscribe(Self::stream_name(symbol, interval)));
+    }
+
+    fn stream_name(symbol: &str, interval: &str) -> String {
+        format!("{}@kline_{}", symbol.to_lowercase(), interval)
+    }
+
+    fn combined_url(base_url: &Url, subscriptions: &Subscriptions) -> Result<Url> {
+        let mut streams = subscriptions.subscribers.keys().cloned().collect::<Vec<String>>();
+        streams.sort();
+
+        let url = format!(
+            "{}/stream?streams={}",
+            base_url.as_str().trim_end_matches('/'),
+            streams.join("/")
+        );
+
+        Ok(Url::parse(&url)?)
+    }
+
+    async fn manage_connection(base_url: Url, mut commands: mpsc::UnboundedReceiver<Command>) {
+        let mut subscriptions = Subscriptions::default();
+
+        loop {
+            while let Ok(command) = commands.try_recv() {
+                subscriptions.apply(command);
+            }
+
+            if subscriptions.subscribers.is_empty() {
+                match commands.recv().await {
+                    Some(command) => {
+                        subscriptions.apply(command);
+                        continue;
+                    }
+                    None => return,
+                }
+            }
+
+            let url = match Self::combined_url(&base_url, &subscriptions) {
+                Ok(url) => url,
+                Err(e) => {
+                    error!("invalid combined stream url: {:?}", e);
+                    return;
+                }
+            };
+
+            // streams in the url are subscribed without a reply
+            subscriptions.pending.clear();
+
+            trace!("connecting to binance combined stream");
+            match connect_async(url.as_str()).await {
+                Ok((mut ws_stream, _)) => {
+                    trace!("connected to binance combined stream");
+                    let (ping_task, mut ping_rx) = Stream::ping_handler().await;
+
+                    loop {
+                        let request = tokio::select! {
+                            _ = ping_rx.recv() => {
+                                if let Err(e) = ws_stream.send(Message::Ping(Bytes::new())).await {
+                                    error!("error sending ping: {:?}", e )
+                                }
+                                None
+                            }
+
+                            command = commands.recv() => match command {
+                                Some(command) => subscriptions.apply(command),
+                                None => {
+                                    ping_task.abort();
+                                    return;
+                                }
+                            },
+
+                            msg = ws_stream.next() => match msg {
+                                Some(Ok(Message::Text(text))) => subscriptions.dispatch(&text),
+                                Some(Ok(Message::Ping(data))) => {
+                                    if let Err(e) = ws_stream.send(Message::Pong(data)).await {
+                                        error!("error sending pong: {:?}", e );
+                                        break;
+                                    }
+                                    None
+                                }
+                                Some(Ok(Message::Close(_))) => {
+                                    error!("received close from server");
+                                    break;
+                                }
+                                Some(Err(e)) => {
+                                    error!("web socket error: {:?}", e);
+                                    break;
+                                }
+                                None => {
+                                    error!("websocket stream ended");
+                                    break
+                                }
+                                _ => None,
+                            },
+                        };
+
+                        if let Some(request) = request {
+                            let text = serde_json::to_string(&request).expect("failed to serialize stream request");
+
+                            if let Err(e) = ws_stream.send(Message::text(text)).await {
+                                error!("error sending {} request: {:?}", request.method, e);
+                                break;
+                            }
+                        }
+                    }
+
+                    ping_task.abort();
+                }
+                Err(e) => {
+                    error!("connection to binance has failed: {:?}", e);
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(RECONNECT_WAIT)).await
+        }
+    }
+}
+
+// The subscribers by stream name and the subscribe requests Binance has not
+// answered yet, so an error reply reaches the subscriber that caused it.
+#[derive(Default)]
+struct Subscriptions {
+    subscribers: Subscribers,
+    pending: HashMap<u64, String>,
+    request_id: u64,
+}
+
+impl Subscriptions {
+    // A stream has one subscriber at a time, a second one gets an error and
+    // the first keeps its stream.
+    fn apply(&mut self, command: Command) -> Option<StreamRequest> {
+        let (method, stream) = match command {
+            Command::Subscribe(stream, tx) => match self.subscribers.contains_key(&stream) {
+                true => {
+                    let _ = tx.try_send(Err(format!("already subscribed to {}", stream).into()));
+                    return None;
+                }
+                false => {
+                    self.subscribers.insert(stream.clone(), tx);
+                    ("SUBSCRIBE", stream)
+                }
+            },
+            Command::Unsubscribe(stream) => match self.subscribers.remove(&stream) {
+                Some(_) => ("UNSUBSCRIBE", stream),
+                None => return None,
+            },
+        };
+
+        self.request_id += 1;
+        if method == "SUBSCRIBE" {
+            self.pending.insert(self.request_id, stream.clone());
+        }
+
+        Some(StreamRequest {
+            method,
+            params: vec![stream],
+            id: self.request_id,
+        })
+    }
+
+    // Events are forwarded without waiting, so a subscriber that stopped
+    // reading can't hold up the others or the ping/pong handling. It is
+    // unsubscribed instead and, once it caught up with the buffered events,
+    // gets an error before its stream ends.
+    fn dispatch(&mut self, text: &str) -> Option<StreamRequest> {
+        match serde_json::from_str::<CombinedMessage>(text) {
+            Ok(CombinedMessage::Event { stream, data }) => match self.subscribers.get(&stream)?.try_send(Ok(*data)) {
+                Ok(()) => None,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    warn!("subscriber for {} is lagging behind, unsubscribing", stream);
+
+                    let tx = self.subscribers.get(&stream)?.clone();
+                    let message = format!("unsubscribed from {} after falling behind", stream);
+                    tokio::spawn(async move {
+                        let _ = tx.send(Err(message.into())).await;
+                    });
+
+                    self.apply(Command::Unsubscribe(stream))
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    trace!("subscriber for {} is gone, unsubscribing", stream);
+                    self.apply(Command::Unsubscribe(stream))
+                }
+            },
+            Ok(CombinedMessage::Error { error, id }) => {
+                let stream = self.pending.remove(&id);
+                error!("binance rejected request {} for {:?}: {}", id, stream, error.msg);
+
+                let tx = self.subscribers.remove(&stream?)?;
+                let message = format!("subscription rejected by binance (code: {}): {}", error.code, error.msg);
+                let _ = tx.try_send(Err(message.into()));
+                None
+            }
+            Ok(CombinedMessage::Response { id }) => {
+                trace!("binance acknowledged request {}", id);
+                self.pending.remove(&id);
+                None
+            }
+            Err(e) => {
+                error!("unexpected combined stream message: {:?}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use url::Url;
+
+    use crate::connectors::binance::test_server::{combined_event, kline_event, serve_ws};
+
+    use super::{Multiplex, SUBSCRIBER_BUFFER};
+
+    const OPEN_TIME: i64 = 1_700_000_040_000;
+
+    #[tokio::test]
+    async fn test_dispatches_events_by_stream() {
+        let mut server = serve_ws(vec![]).await;
+        let multiplex = Multiplex::new(Url::parse(&server.url).unwrap());
+
+        let mut btc = multiplex.subscribe("BTCUSDT", "1m");
+        let mut eth = multiplex.subscribe("ETHUSDT", "1m");
+
+        assert_eq!(
+            server.paths.recv().await.unwrap(),
+            "/stream?streams=btcusdt@kline_1m/ethusdt@kline_1m"
+        );
+
+        server.send(combined_event(
+            "ethusdt@kline_1m",
+            kline_event("ETHUSDT", "1m", OPEN_TIME, 2000.0, true),
+        ));
+        server.send(combined_event(
+            "btcusdt@kline_1m",
+            kline_event("BTCUSDT", "1m", OPEN_TIME, 40000.0, true),
+        ));
+
+        let btc_kline = btc.next().await.unwrap().unwrap();
+        let eth_kline = eth.next().await.unwrap().unwrap();
+
+        assert_eq!(btc_kline.symbol, "BTCUSDT");
+        assert_eq!(btc_kline.close, 40000.0);
+        assert_eq!(eth_kline.symbol, "ETHUSDT");
+        assert_eq!(eth_kline.close, 2000.0);
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_change_without_reconnecting() {
+        let mut server = serve_ws(vec![]).await;
+        let multiplex = Multiplex::new(Url::parse(&server.url).unwrap());
+
+        let _btc = multiplex.subscribe("BTCUSDT", "1m");
+        server.paths.recv().await.unwrap();
+
+        let mut eth = multiplex.subscribe("ETHUSDT", "1m");
+        assert_eq!(
+            server.received.recv().await.unwrap(),
+            r#"{"method":"SUBSCRIBE","params":["ethusdt@kline_1m"],"id":2}"#
+        );
+
+        server.send(r#"{"result":null,"id":2}"#.to_string());
+        server.send(combined_event(
+            "ethusdt@kline_1m",
+            kline_event("ETHUSDT", "1m", OPEN_TIME, 2000.0, true),
+        ));
+        assert_eq!(eth.next().await.unwrap().unwrap().symbol, "ETHUSDT");
+
+        multiplex.unsubscribe("ETHUSDT", "1m");
+        assert_eq!(
+            server.received.recv().await.unwrap(),
+            r#"{"method":"UNSUBSCRIBE","params":["ethusdt@kline_1m"],"id":3}"#
+        );
+        assert!(eth.next().await.is_none());
+        assert!(server.paths.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lagging_subscriber_does_not_block_others() {
+        let mut server = serve_ws(vec![]).await;
+        let multiplex = Multiplex::new(Url::parse(&server.url).unwrap());
+
+        let btc = multiplex.subscribe("BTCUSDT", "1m");
+        let mut eth = multiplex.subscribe("ETHUSDT", "1m");
+        server.paths.recv().await.unwrap();
+
+        // nobody reads the BTC stream for now
+        for i in 0..=SUBSCRIBER_BUFFER as i64 {
+            server.send(combined_event(
+                "btcusdt@kline_1m",
+                kline_event("BTCUSDT", "1m", OPEN_TIME + i * 60_000, 40000.0, true),
+            ));
+        }
+        server.send(combined_event(
+            "ethusdt@kline_1m",
+            kline_event("ETHUSDT", "1m", OPEN_TIME, 2000.0, true),
+        ));
+
+        assert_eq!(
+            server.received.recv().await.unwrap(),
+            r#"{"method":"UNSUBSCRIBE","params":["btcusdt@kline_1m"],"id":3}"#
+        );
+        assert_eq!(eth.next().await.unwrap().unwrap().symbol, "ETHUSDT");
+
+        let btc = btc.collect::<Vec<_>>().await;
+        assert_eq!(btc.len(), SUBSCRIBER_BUFFER + 1);
+        assert!(btc[..SUBSCRIBER_BUFFER].iter().all(|event| event.is_ok()));
+        assert!(btc[SUBSCRIBER_BUFFER].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_second_subscriber_is_rejected() {
+        let mut server = serve_ws(vec![]).await;
+        let multiplex = Multiplex::new(Url::parse(&server.url).unwrap());
+
+        let mut first = multiplex.subscribe("BTCUSDT", "1m");
+        let mut second = multiplex.subscribe("BTCUSDT", "1m");
+        server.paths.recv().await.unwrap();
+
+        assert!(second.next().await.unwrap().is_err());
+        assert!(second.next().await.is_none());
+
+        server.send(combined_event(
+            "btcusdt@kline_1m",
+            kline_event("BTCUSDT", "1m", OPEN_TIME, 40000.0, true),
+        ));
+        assert_eq!(first.next().await.unwrap().unwrap().close, 40000.0);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_subscription_reaches_subscriber() {
+        let mut server = serve_ws(vec![]).await;
+        let multiplex = Multiplex::new(Url::parse(&server.url).unwrap());
+
+        let _btc = multiplex.subscribe("BTCUSDT", "1m");
+        server.paths.recv().await.unwrap();
+
+        let mut eth = multiplex.subscribe("ETHUSDT", "1m");
+        server.received.recv().await.unwrap();
+        server.send(r#"{"error":{"code":2,"msg":"Invalid request"},"id":2}"#.to_string());
+
+        assert!(eth.next().await.unwrap().is_err());
+        assert!(eth.next().await.is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use std::{future::Future, pin::Pin};
 
-use futures::Stream;
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream};
 
 use crate::{
     data_structures::kline::Kline,
@@ -23,10 +24,29 @@ impl Source for Binance {
     }
 
     fn fetch_history(&self) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
-        Box::pin(async move { self.fetch_historical_klines().await })
+        Box::pin(async move {
+            let klines = self.fetch_historical_klines().await?;
+            self.continuity.record_history(&klines);
+            Ok(klines)
+        })
+    }
+
+    fn fetch_history_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
+        Box::pin(async move {
+            let klines = self.fetch_klines_range(start, end).await?;
+            self.continuity.record_history(&klines);
+            Ok(klines)
+        })
     }
 
     fn fetch_live(&self) -> Pin<Box<dyn Stream<Item = Result<Kline>> + Send>> {
-        Box::pin(super::stream::Stream::new())
+        match self.kline_stream_url() {
+            Ok(url) => self.backfilled(super::stream::Stream::new(url)),
+            Err(e) => Box::pin(stream::once(async move { Err(e) })),
+        }
     }
 }
@@ -16,7 +16,7 @@ use tokio_tungstenite::{
 };
 use url::Url;
 
-const RECONNECT_WAIT: u64 = 5;
+pub(super) const RECONNECT_WAIT: u64 = 5;
 const PING_INTERVAL_IN_SECONDS: u64 = 60 * 10;
 
 pub struct Stream {
@@ -44,19 +44,21 @@ impl futures_util::stream::Stream for Stream {
 }
 
 impl Stream {
-    pub fn new() -> Self {
-        let url = Url::parse("wss://fstream.binance.com/ws/btcusdt@kline_1m").unwrap();
-
+    pub fn new(url: Url) -> Self {
         let (tx, rx) = mpsc::channel(100);
 
         tokio::spawn(async move {
             Self::manage_connection(url, tx).await;
         });
 
-        Self { receiver: rx }
+        Self::with_receiver(rx)
+    }
+
+    pub(super) fn with_receiver(receiver: mpsc::Receiver<Result<KlineEvent>>) -> Self {
+        Self { receiver }
     }
 
-    async fn ping_handler() -> (task::JoinHandle<()>, mpsc::Receiver<()>) {
+    pub(super) async fn ping_handler() -> (task::JoinHandle<()>, mpsc::Receiver<()>) {
         let (tx, rx) = mpsc::channel(1);
 
         (
@@ -108,10 +110,8 @@ impl Stream {
 
                             msg = ws_stream.next() => {
                                 match msg {
-                                    Some(Ok(Message::Text(text))) => {
-                                        if !Self::handle_text_message(text.to_string(), &tx).await {
-                                            break
-                                        }
+                                    Some(Ok(Message::Text(text))) if !Self::handle_text_message(text.to_string(), &tx).await => {
+                                        break
                                     }
                                     Some(Ok(Message::Ping(data))) => {
                                         if let Err(e) = ws_stream.send(Message::Pong(data)).await {
@@ -149,3 +149,29 @@ impl Stream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+
+    use crate::connectors::binance::{
+        test_server::{kline_event, serve_ws},
+        Binance,
+    };
+    use crate::source::Source;
+
+    #[tokio::test]
+    async fn test_stream_uses_symbol_and_interval() {
+        let mut server = serve_ws(vec![kline_event("ETHUSDT", "5m", 1_700_000_100_000, 2000.0, true)]).await;
+        let binance = Binance::new("ETHUSDT".to_string(), "5m".to_string())
+            .await
+            .with_stream_url(server.url.clone());
+
+        let mut stream = binance.fetch_live();
+        let kline = stream.next().await.unwrap().unwrap();
+
+        assert_eq!(server.paths.recv().await.unwrap(), "/ws/ethusdt@kline_5m");
+        assert_eq!(kline.symbol, "ETHUSDT");
+        assert_eq!(kline.close, 2000.0);
+    }
+}
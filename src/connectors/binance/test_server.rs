@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    sync::{broadcast, mpsc},
+};
+use tokio_tungstenite::{
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{Request, Response},
+        Message,
+    },
+};
+use url::Url;
+
+pub(crate) struct HttpResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl HttpResponse {
+    pub(crate) fn new(status: u16, body: String) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    pub(crate) fn ok(body: String) -> Self {
+        Self::new(200, body)
+    }
+
+    pub(crate) fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+// Minimal HTTP/1.1 stand-in for the Binance REST API. The handler receives the
+// request target (path and query) and one connection serves one request.
+pub(crate) async fn serve_http<F>(handler: F) -> String
+where
+    F: Fn(&str) -> HttpResponse + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let handler = Arc::new(handler);
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let handler = handler.clone();
+
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buffer).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buffer[..n]),
+                    }
+                }
+
+                let request = String::from_utf8_lossy(&request);
+                let target = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let response = handler(&target);
+
+                let mut raw = format!(
+                    "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    response.status,
+                    response.body.len()
+                );
+                for (name, value) in response.headers {
+                    raw.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                raw.push_str("\r\n");
+                raw.push_str(&response.body);
+
+                let _ = socket.write_all(raw.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            });
+        }
+    });
+
+    format!("http://{}", address)
+}
+
+pub(crate) fn query_param(target: &str, name: &str) -> Option<String> {
+    let url = Url::parse(&format!("http://localhost{}", target)).ok()?;
+
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+// Renders klines in the `/api/v3/klines` array format, with the open time as
+// the price so tests can tell bars apart.
+pub(crate) fn klines_page(open_times: &[i64]) -> String {
+    let rows = open_times
+        .iter()
+        .map(|time| {
+            let price = (*time / 60_000) as f64;
+            format!(
+                r#"[{},"{:.2}","{:.2}","{:.2}","{:.2}","1.0",{},"0",1,"0","0","0"]"#,
+                time,
+                price,
+                price + 1.0,
+                price - 1.0,
+                price,
+                time + 59_999
+            )
+        })
+        .collect::<Vec<String>>();
+
+    format!("[{}]", rows.join(","))
+}
+
+pub(crate) struct WsServer {
+    pub(crate) url: String,
+    pub(crate) paths: mpsc::UnboundedReceiver<String>,
+    pub(crate) received: mpsc::UnboundedReceiver<String>,
+    outgoing: broadcast::Sender<String>,
+}
+
+impl WsServer {
+    pub(crate) fn send(&self, message: String) {
+        let _ = self.outgoing.send(message);
+    }
+}
+
+// Websocket stand-in for the Binance streams. Every connection receives the
+// initial messages followed by anything pushed through `WsServer::send`. The
+// request path of each connection and every text frame sent by clients are
+// reported through `paths` and `received`.
+#[allow(clippy::result_large_err)]
+pub(crate) async fn serve_ws(messages: Vec<String>) -> WsServer {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let (paths_tx, paths) = mpsc::unbounded_channel();
+    let (received_tx, received) = mpsc::unbounded_channel();
+    let (outgoing, _) = broadcast::channel(100);
+    let broadcaster = outgoing.clone();
+
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let messages = messages.clone();
+            let paths_tx = paths_tx.clone();
+            let received_tx = received_tx.clone();
+            let mut outgoing = broadcaster.subscribe();
+
+            tokio::spawn(async move {
+                let callback = |request: &Request, response: Response| {
+                    let _ = paths_tx.send(request.uri().to_string());
+                    Ok(response)
+                };
+                let Ok(mut ws_stream) = accept_hdr_async(socket, callback).await else {
+                    return;
+                };
+
+                for message in messages {
+                    if ws_stream.send(Message::text(message)).await.is_err() {
+                        return;
+                    }
+                }
+
+                loop {
+                    tokio::select! {
+                        message = outgoing.recv() => match message {
+                            Ok(message) => {
+                                if ws_stream.send(Message::text(message)).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Err(_) => return,
+                        },
+                        message = ws_stream.next() => match message {
+                            Some(Ok(Message::Text(text))) => {
+                                let _ = received_tx.send(text.to_string());
+                            }
+                            Some(Ok(_)) => {}
+                            _ => return,
+                        },
+                    }
+                }
+            });
+        }
+    });
+
+    WsServer {
+        url: format!("ws://{}", address),
+        paths,
+        received,
+        outgoing,
+    }
+}
+
+pub(crate) fn kline_event(symbol: &str, interval: &str, open_time: i64, close: f64, closed: bool) -> String {
+    format!(
+        r#"{{"e":"kline","E":{},"s":"{}","k":{{"t":{},"T":{},"s":"{}","i":"{}","f":1,"L":2,"o":"{:.2}","c":"{:.2}","h":"{:.2}","l":"{:.2}","v":"1.0","n":2,"x":{},"q":"0","V":"0","Q":"0"}}}}"#,
+        open_time + 1,
+        symbol,
+        open_time,
+        open_time + 59_999,
+        symbol,
+        interval,
+        close,
+        close,
+        close + 1.0,
+        close - 1.0,
+        closed
+    )
+}
+
+pub(crate) fn combined_event(stream: &str, event: String) -> String {
+    format!(r#"{{"stream":"{}","data":{}}}"#, stream, event)
+}
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use serde_json::Value;
+
+use crate::{data_structures::kline::Kline, source::Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Format {
+    Csv { delimiter: char, header: Header },
+    JsonLines,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Header {
+    Present,
+    Absent,
+    // A first row starting with something else than a number is the header,
+    // for files that only sometimes have one.
+    Detect,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Columns {
+    pub time: Column,
+    pub open: Column,
+    pub high: Column,
+    pub low: Column,
+    pub close: Column,
+    pub volume: Column,
+}
+
+impl Columns {
+    pub fn named() -> Self {
+        Self {
+            time: Column::Name("time".to_string()),
+            open: Column::Name("open".to_string()),
+            high: Column::Name("high".to_string()),
+            low: Column::Name("low".to_string()),
+            close: Column::Name("close".to_string()),
+            volume: Column::Name("volume".to_string()),
+        }
+    }
+
+    pub fn binance() -> Self {
+        Self {
+            time: Column::Index(0),
+            open: Column::Index(1),
+            high: Column::Index(2),
+            low: Column::Index(3),
+            close: Column::Index(4),
+            volume: Column::Index(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeUnit {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+    // Guessed per value from its magnitude.
+    Auto,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeFormat {
+    Unix(TimeUnit),
+    Rfc3339,
+    // A `chrono` format string for naive date times in the given offset.
+    Text { format: String, offset: FixedOffset },
+}
+
+impl TimeFormat {
+    fn parse(&self, value: &str) -> Result<DateTime<Utc>> {
+        let time = match self {
+            TimeFormat::Unix(unit) => {
+                let value: i64 = value.parse().map_err(|_| format!("invalid timestamp: {}", value))?;
+                let unit = match unit {
+                    TimeUnit::Auto if value.abs() >= 100_000_000_000_000_000 => TimeUnit::Nanoseconds,
+                    TimeUnit::Auto if value.abs() >= 100_000_000_000_000 => TimeUnit::Microseconds,
+                    TimeUnit::Auto if value.abs() >= 100_000_000_000 => TimeUnit::Milliseconds,
+                    TimeUnit::Auto => TimeUnit::Seconds,
+                    unit => *unit,
+                };
+
+                match unit {
+                    TimeUnit::Seconds => DateTime::from_timestamp(value, 0),
+                    TimeUnit::Milliseconds => DateTime::from_timestamp_millis(value),
+                    TimeUnit::Microseconds => DateTime::from_timestamp_micros(value),
+                    _ => Some(DateTime::from_timestamp_nanos(value)),
+                }
+            }
+            TimeFormat::Rfc3339 => DateTime::parse_from_rfc3339(value).ok().map(|t| t.to_utc()),
+            TimeFormat::Text { format, offset } => NaiveDateTime::parse_from_str(value, format)
+                .ok()
+                .and_then(|t| t.and_local_timezone(*offset).single())
+                .map(|t| t.to_utc()),
+        };
+
+        time.ok_or_else(|| format!("invalid time: {}", value).into())
+    }
+}
+
+impl Format {
+    pub(super) fn parse(&self, content: &str, columns: &Columns, time_format: &TimeFormat) -> Result<Vec<Kline>> {
+        match self {
+            Format::Csv { delimiter, header } => Self::parse_csv(content, *delimiter, *header, columns, time_format),
+            Format::JsonLines => Self::parse_json_lines(content, columns, time_format),
+        }
+    }
+
+    fn parse_csv(
+        content: &str,
+        delimiter: char,
+        header: Header,
+        columns: &Columns,
+        time_format: &TimeFormat,
+    ) -> Result<Vec<Kline>> {
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .peekable();
+        let header = match header {
+            Header::Present => true,
+            Header::Absent => false,
+            Header::Detect => lines
+                .peek()
+                .is_some_and(|(_, line)| Self::split_csv_line(line, delimiter)[0].trim().parse::<f64>().is_err()),
+        };
+        let names = match header {
+            true => match lines.next() {
+                Some((_, line)) => Self::split_csv_line(line, delimiter)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, name)| (name.trim().to_string(), index))
+                    .collect(),
+                None => return Ok(Vec::new()),
+            },
+            false => HashMap::new(),
+        };
+
+        let index = |column: &Column| -> Result<usize> {
+            match column {
+                Column::Index(index) => Ok(*index),
+                Column::Name(name) => names
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| format!("column {} not found in header", name).into()),
+            }
+        };
+        let indexes = [
+            index(&columns.time)?,
+            index(&columns.open)?,
+            index(&columns.high)?,
+            index(&columns.low)?,
+            index(&columns.close)?,
+            index(&columns.volume)?,
+        ];
+
+        lines
+            .map(|(number, line)| {
+                let fields = Self::split_csv_line(line, delimiter);
+                let field = |i: usize| {
+                    fields
+                        .get(indexes[i])
+                        .map(|field| field.trim())
+                        .ok_or_else(|| format!("line {}: missing column {}", number + 1, indexes[i]))
+                };
+                let price = |i: usize| -> Result<f64> {
+                    let value = field(i)?;
+                    value
+                        .parse()
+                        .map_err(|_| format!("line {}: invalid number {}", number + 1, value).into())
+                };
+
+                Ok(Kline {
+                    time: time_format
+                        .parse(field(0)?)
+                        .map_err(|e| format!("line {}: {}", number + 1, e))?,
+                    open: price(1)?,
+                    high: price(2)?,
+                    low: price(3)?,
+                    close: price(4)?,
+                    volume: price(5)?,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    fn split_csv_line(line: &str, delimiter: char) -> Vec<&str> {
+        let mut fields = Vec::new();
+        let mut start = 0;
+        let mut quoted = false;
+
+        for (i, c) in line.char_indices() {
+            if c == '"' {
+                quoted = !quoted;
+            } else if c == delimiter && !quoted {
+                fields.push(line[start..i].trim_matches('"'));
+                start = i + c.len_utf8();
+            }
+        }
+        fields.push(line[start..].trim_matches('"'));
+
+        fields
+    }
+
+    fn parse_json_lines(content: &str, columns: &Columns, time_format: &TimeFormat) -> Result<Vec<Kline>> {
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(number, line)| {
+                let value: Value = serde_json::from_str(line).map_err(|e| format!("line {}: {}", number + 1, e))?;
+                let field = |column: &Column| -> Result<String> {
+                    let field = match column {
+                        Column::Index(index) => value.get(index),
+                        Column::Name(name) => value.get(name),
+                    };
+
+                    match field {
+                        Some(Value::String(s)) => Ok(s.clone()),
+                        Some(Value::Number(n)) => Ok(n.to_string()),
+                        _ => Err(format!("line {}: missing field {:?}", number + 1, column).into()),
+                    }
+                };
+                let price = |column: &Column| -> Result<f64> {
+                    let value = field(column)?;
+                    value
+                        .parse()
+                        .map_err(|_| format!("line {}: invalid number {}", number + 1, value).into())
+                };
+
+                Ok(Kline {
+                    time: time_format
+                        .parse(&field(&columns.time)?)
+                        .map_err(|e| format!("line {}: {}", number + 1, e))?,
+                    open: price(&columns.open)?,
+                    high: price(&columns.high)?,
+                    low: price(&columns.low)?,
+                    close: price(&columns.close)?,
+                    volume: price(&columns.volume)?,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,85 @@
+pub mod format;
+mod source;
+
+use std::path::PathBuf;
+
+use chrono::FixedOffset;
+
+use format::{Columns, Format, Header, TimeFormat, TimeUnit};
+
+use crate::source::Result;
+
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    name: String,
+    path: PathBuf,
+    symbol: String,
+    interval: String,
+    format: Format,
+    columns: Columns,
+    time_format: TimeFormat,
+    replay: Option<usize>,
+}
+
+impl FileSource {
+    pub fn new(path: PathBuf, symbol: String, interval: String, format: Format) -> Self {
+        FileSource {
+            name: "file".to_string(),
+            path,
+            symbol,
+            interval,
+            format,
+            columns: Columns::named(),
+            time_format: TimeFormat::Unix(TimeUnit::Milliseconds),
+            replay: None,
+        }
+    }
+
+    // Files from data.binance.vision: a header row in futures data only, open
+    // time in milliseconds (microseconds for spot data since 2025) and the
+    // usual column order.
+    pub fn binance_dump(path: PathBuf, symbol: String, interval: String) -> Self {
+        Self::new(
+            path,
+            symbol,
+            interval,
+            Format::Csv {
+                delimiter: ',',
+                header: Header::Detect,
+            },
+        )
+        .with_columns(Columns::binance())
+        .with_time_format(TimeFormat::Unix(TimeUnit::Auto))
+    }
+
+    pub fn with_columns(mut self, columns: Columns) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_time_format(mut self, time_format: TimeFormat) -> Self {
+        self.time_format = time_format;
+        self
+    }
+
+    // Naive date times in the file are interpreted in the given offset. Only
+    // `TimeFormat::Text` has naive times, Unix and RFC 3339 timestamps already
+    // carry their offset, so any other time format is an error. Set the time
+    // format first.
+    pub fn with_offset(mut self, offset: FixedOffset) -> Result<Self> {
+        match self.time_format {
+            TimeFormat::Text { format, .. } => {
+                self.time_format = TimeFormat::Text { format, offset };
+                Ok(self)
+            }
+            time_format => Err(format!("an offset does not apply to {:?} times", time_format).into()),
+        }
+    }
+
+    // Holds back the last `rows` klines from `fetch_history` and serves them
+    // through `fetch_live` instead.
+    pub fn with_replay(mut self, rows: usize) -> Self {
+        self.replay = Some(rows);
+        self
+    }
+}
@@ -0,0 +1,241 @@
+use std::{future::Future, pin::Pin};
+
+use futures::{stream, Stream, StreamExt};
+
+use crate::{
+    data_structures::kline::Kline,
+    source::{Result, Source},
+};
+
+use super::FileSource;
+
+impl FileSource {
+    pub async fn load_klines(&self) -> Result<Vec<Kline>> {
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|e| format!("failed to read {}: {}", self.path.display(), e))?;
+
+        let mut klines = self
+            .format
+            .parse(&content, &self.columns, &self.time_format)?
+            .into_iter()
+            .map(|mut kline| {
+                kline.symbol = self.symbol.clone();
+                kline
+            })
+            .collect::<Vec<Kline>>();
+
+        klines.sort_by_key(|kline| kline.time);
+        klines.dedup_by_key(|kline| kline.time);
+
+        Ok(klines)
+    }
+
+    fn split_point(&self, len: usize) -> usize {
+        len - self.replay.unwrap_or(0).min(len)
+    }
+}
+
+impl Source for FileSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn timeframe(&self) -> &str {
+        &self.interval
+    }
+
+    fn fetch_history(&self) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
+        Box::pin(async move {
+            let mut klines = self.load_klines().await?;
+            klines.truncate(self.split_point(klines.len()));
+
+            Ok(klines)
+        })
+    }
+
+    fn fetch_live(&self) -> Pin<Box<dyn Stream<Item = Result<Kline>> + Send>> {
+        if self.replay.is_none() {
+            return Box::pin(stream::empty());
+        }
+
+        let source = self.clone();
+
+        Box::pin(
+            stream::once(async move {
+                match source.load_klines().await {
+                    Ok(mut klines) => {
+                        let replayed = klines.split_off(source.split_point(klines.len()));
+                        replayed.into_iter().map(Ok).collect::<Vec<Result<Kline>>>()
+                    }
+                    Err(e) => vec![Err(e)],
+                }
+            })
+            .flat_map(stream::iter),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+    use futures::StreamExt;
+
+    use crate::connectors::file::{
+        format::{Column, Columns, Format, Header, TimeFormat, TimeUnit},
+        FileSource,
+    };
+    use crate::source::Source;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(name)
+    }
+
+    #[tokio::test]
+    async fn test_binance_dump() {
+        let source = FileSource::binance_dump(fixture("BTCUSDT-1m.csv"), "BTCUSDT".to_string(), "1m".to_string());
+
+        let klines = source.fetch_history().await.unwrap();
+
+        assert_eq!(klines.len(), 10);
+        assert_eq!(klines[0].time, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(klines[0].symbol, "BTCUSDT");
+        assert_eq!(klines[0].open, 42283.58);
+        assert_eq!(klines[0].high, 42298.62);
+        assert_eq!(klines[0].low, 42261.02);
+        assert_eq!(klines[0].close, 42298.61);
+        assert_eq!(klines[0].volume, 35.92724);
+        assert_eq!(klines[9].time, Utc.with_ymd_and_hms(2024, 1, 1, 0, 9, 0).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_binance_dump_in_microseconds() {
+        let source = FileSource::binance_dump(fixture("BTCUSDT-1m-us.csv"), "BTCUSDT".to_string(), "1m".to_string());
+
+        let klines = source.fetch_history().await.unwrap();
+
+        assert_eq!(klines.len(), 3);
+        assert_eq!(klines[0].time, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_binance_futures_dump_with_header() {
+        let source = FileSource::binance_dump(
+            fixture("BTCUSDT-1m-futures.csv"),
+            "BTCUSDT".to_string(),
+            "1m".to_string(),
+        );
+
+        let klines = source.fetch_history().await.unwrap();
+
+        assert_eq!(klines.len(), 3);
+        assert_eq!(klines[0].time, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(klines[0].open, 42283.58);
+    }
+
+    #[tokio::test]
+    async fn test_json_lines_with_column_mapping() {
+        let columns = Columns {
+            time: Column::Name("ts".to_string()),
+            open: Column::Name("o".to_string()),
+            high: Column::Name("h".to_string()),
+            low: Column::Name("l".to_string()),
+            close: Column::Name("c".to_string()),
+            volume: Column::Name("v".to_string()),
+        };
+        let source = FileSource::new(
+            fixture("ETHUSDT-1h.jsonl"),
+            "ETHUSDT".to_string(),
+            "1h".to_string(),
+            Format::JsonLines,
+        )
+        .with_columns(columns)
+        .with_time_format(TimeFormat::Text {
+            format: "%Y-%m-%d %H:%M:%S".to_string(),
+            offset: FixedOffset::east_opt(0).unwrap(),
+        })
+        .with_offset(FixedOffset::east_opt(2 * 3600).unwrap())
+        .unwrap();
+
+        let klines = source.fetch_history().await.unwrap();
+
+        assert_eq!(klines.len(), 3);
+        assert_eq!(klines[0].time, Utc.with_ymd_and_hms(2023, 12, 31, 22, 0, 0).unwrap());
+        assert_eq!(klines[1].open, 2290.5);
+        assert_eq!(klines[1].volume, 1200.0);
+    }
+
+    #[test]
+    fn test_offset_needs_naive_times() {
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        let source = |time_format| {
+            FileSource::new(
+                fixture("ETHUSDT-1h.jsonl"),
+                "ETHUSDT".to_string(),
+                "1h".to_string(),
+                Format::JsonLines,
+            )
+            .with_time_format(time_format)
+        };
+
+        assert!(source(TimeFormat::Unix(TimeUnit::Seconds)).with_offset(offset).is_err());
+        assert!(source(TimeFormat::Rfc3339).with_offset(offset).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_csv_with_header_and_seconds() {
+        let source = FileSource::new(
+            fixture("SOLUSDT-1d.csv"),
+            "SOLUSDT".to_string(),
+            "1d".to_string(),
+            Format::Csv {
+                delimiter: ';',
+                header: Header::Present,
+            },
+        )
+        .with_time_format(TimeFormat::Unix(TimeUnit::Seconds));
+
+        let klines = source.fetch_history().await.unwrap();
+
+        assert_eq!(klines.len(), 2);
+        assert_eq!(
+            klines[0].time,
+            DateTime::<Utc>::from_timestamp(1_704_067_200, 0).unwrap()
+        );
+        assert_eq!(klines[1].close, 108.25);
+    }
+
+    #[tokio::test]
+    async fn test_replays_trailing_rows_through_live() {
+        let source =
+            FileSource::binance_dump(fixture("BTCUSDT-1m.csv"), "BTCUSDT".to_string(), "1m".to_string()).with_replay(4);
+
+        let history = source.fetch_history().await.unwrap();
+        let live = source.fetch_live().map(|k| k.unwrap()).collect::<Vec<_>>().await;
+
+        assert_eq!(history.len(), 6);
+        assert_eq!(live.len(), 4);
+        assert!(history.last().unwrap().time < live[0].time);
+    }
+
+    #[tokio::test]
+    async fn test_reports_malformed_rows() {
+        let source = FileSource::new(
+            fixture("SOLUSDT-1d.csv"),
+            "SOLUSDT".to_string(),
+            "1d".to_string(),
+            Format::Csv {
+                delimiter: ',',
+                header: Header::Present,
+            },
+        );
+
+        assert!(source.fetch_history().await.is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+
+use super::kline::Kline;
+
+// Klines stored column by column and ordered by open time, so indicators can
+// read prices as contiguous slices without copying.
+#[derive(Debug, Default, Clone)]
+pub struct Bars {
+    symbol: String,
+    time: Vec<DateTime<Utc>>,
+    open: Vec<f64>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    volume: Vec<f64>,
+    closed: Vec<bool>,
+}
+
+// A read-only view over consecutive bars.
+#[derive(Debug, Clone, Copy)]
+pub struct Window<'a> {
+    pub symbol: &'a str,
+    pub time: &'a [DateTime<Utc>],
+    pub open: &'a [f64],
+    pub high: &'a [f64],
+    pub low: &'a [f64],
+    pub close: &'a [f64],
+    pub volume: &'a [f64],
+    pub closed: &'a [bool],
+}
+
+pub enum Placement {
+    Appended,
+    Replaced(usize),
+    Inserted(usize),
+}
+
+impl Bars {
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+
+    // Appends `kline`, replaces the bar with the same open time or inserts it
+    // in order when it is older than the last bar.
+    pub fn insert(&mut self, kline: Kline) -> Placement {
+        let position = match self.time.last() {
+            Some(last) if *last < kline.time => None,
+            None => None,
+            Some(_) => Some(self.time.binary_search(&kline.time)),
+        };
+
+        self.symbol = kline.symbol;
+
+        match position {
+            None => {
+                self.time.push(kline.time);
+                self.open.push(kline.open);
+                self.high.push(kline.high);
+                self.low.push(kline.low);
+                self.close.push(kline.close);
+                self.volume.push(kline.volume);
+                self.closed.push(kline.closed);
+                Placement::Appended
+            }
+            Some(Ok(index)) => {
+                self.open[index] = kline.open;
+                self.high[index] = kline.high;
+                self.low[index] = kline.low;
+                self.close[index] = kline.close;
+                self.volume[index] = kline.volume;
+                self.closed[index] = kline.closed;
+                Placement::Replaced(index)
+            }
+            Some(Err(index)) => {
+                self.time.insert(index, kline.time);
+                self.open.insert(index, kline.open);
+                self.high.insert(index, kline.high);
+                self.low.insert(index, kline.low);
+                self.close.insert(index, kline.close);
+                self.volume.insert(index, kline.volume);
+                self.closed.insert(index, kline.closed);
+                Placement::Inserted(index)
+            }
+        }
+    }
+
+    pub fn position(&self, time: DateTime<Utc>) -> Option<usize> {
+        self.time.binary_search(&time).ok()
+    }
+
+    pub fn kline(&self, index: usize) -> Kline {
+        self.range(index, index + 1).kline(0)
+    }
+
+    // The last `count` bars.
+    pub fn window(&self, count: usize) -> Window<'_> {
+        self.range(self.len().saturating_sub(count), self.len())
+    }
+
+    pub fn range(&self, start: usize, end: usize) -> Window<'_> {
+        Window {
+            symbol: &self.symbol,
+            time: &self.time[start..end],
+            open: &self.open[start..end],
+            high: &self.high[start..end],
+            low: &self.low[start..end],
+            close: &self.close[start..end],
+            volume: &self.volume[start..end],
+            closed: &self.closed[start..end],
+        }
+    }
+
+    // Drops the `count` oldest bars.
+    pub fn drain_front(&mut self, count: usize) {
+        self.time.drain(..count);
+        self.open.drain(..count);
+        self.high.drain(..count);
+        self.low.drain(..count);
+        self.close.drain(..count);
+        self.volume.drain(..count);
+        self.closed.drain(..count);
+    }
+}
+
+impl Window<'_> {
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+
+    pub fn kline(&self, index: usize) -> Kline {
+        Kline {
+            time: self.time[index],
+            symbol: self.symbol.to_string(),
+            open: self.open[index],
+            close: self.close[index],
+            low: self.low[index],
+            high: self.high[index],
+            volume: self.volume[index],
+            closed: self.closed[index],
+        }
+    }
+
+    pub fn klines(&self) -> Vec<Kline> {
+        (0..self.len()).map(|index| self.kline(index)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::data_structures::kline::helpers::generate_klines_with_interval;
+
+    use super::Bars;
+
+    #[test]
+    fn test_insert_keeps_bars_ordered() {
+        let klines = generate_klines_with_interval(Utc::now(), &[1.0, 2.0, 3.0, 4.0], 60);
+        let mut bars = Bars::default();
+
+        for index in [0, 3, 1] {
+            bars.insert(klines[index].clone());
+        }
+        bars.insert(klines[2].clone());
+        bars.insert(klines[1].clone());
+
+        assert_eq!(bars.len(), 4);
+        assert_eq!(bars.window(10).close, &[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(bars.window(2).klines(), klines[2..].to_vec());
+    }
+
+    #[test]
+    fn test_drain_front() {
+        let klines = generate_klines_with_interval(Utc::now(), &[1.0, 2.0, 3.0, 4.0], 60);
+        let mut bars = Bars::default();
+
+        for kline in klines.clone() {
+            bars.insert(kline);
+        }
+        bars.drain_front(3);
+
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars.kline(0), klines[3]);
+    }
+}
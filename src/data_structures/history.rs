@@ -1,22 +1,45 @@
 use chrono::{DateTime, Utc};
 use core::fmt;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::HashMap;
 
-use crate::indicators::{self, Indicator, IndicatorIdentifier};
+use crate::indicators::{self, nested::Input, Indicator, IndicatorIdentifier, Readiness};
 
-use super::kline::Kline;
+use super::{
+    bars::{Bars, Placement, Window},
+    interval::Interval,
+    kline::Kline,
+    resampler::Resampler,
+};
+
+// Indicator values aligned with the bars; `None` for bars inserted before the
+// indicator was requested or before it warmed up.
+type Values = Vec<Option<Vec<f64>>>;
+type Calculators = HashMap<IndicatorIdentifier, Box<dyn Indicator>>;
 
 #[derive(Default)]
 pub struct History {
-    data: BTreeMap<DateTime<Utc>, Kline>,
-    calculators: HashMap<IndicatorIdentifier, Box<dyn Indicator>>,
-    indicators: BTreeMap<DateTime<Utc>, HashMap<IndicatorIdentifier, Vec<f64>>>,
+    bars: Bars,
+    calculators: Calculators,
+    // the calculators with the inputs of nested indicators before them
+    order: Vec<IndicatorIdentifier>,
+    indicators: HashMap<IndicatorIdentifier, Values>,
+    retention: Option<usize>,
+    // bars dropped from the front because of the retention
+    offset: usize,
+    interval: Option<Interval>,
+    timeframes: HashMap<Interval, Timeframe>,
+}
+
+// A higher timeframe built from the klines inserted into the base history.
+struct Timeframe {
+    resampler: Resampler,
+    history: History,
 }
 
 impl fmt::Debug for History {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("History")
-            .field("data", &self.data)
+            .field("bars", &self.bars)
             // .field("indicators", &self.indicators)
             .finish()
     }
@@ -25,44 +48,280 @@ impl fmt::Debug for History {
 impl History {
     pub fn new() -> Self {
         History {
-            data: BTreeMap::new(),
+            bars: Bars::default(),
             calculators: HashMap::new(),
-            indicators: BTreeMap::new(),
+            order: Vec::new(),
+            indicators: HashMap::new(),
+            retention: None,
+            offset: 0,
+            interval: None,
+            timeframes: HashMap::new(),
         }
     }
 
     pub fn with_klines(klines: Vec<Kline>) -> Self {
-        let data = BTreeMap::from_iter(klines.clone().into_iter().map(|k| (k.time, k)));
+        let mut bars = Bars::default();
+        for kline in klines {
+            bars.insert(kline);
+        }
 
         History {
-            data,
+            bars,
             ..Default::default()
         }
     }
 
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    // Keeps at most `bars` bars, dropping the oldest ones as new bars arrive,
+    // so a live history does not grow without bounds. Older bars are dropped
+    // in batches, so up to twice as many bars may be held at times.
+    pub fn with_retention(mut self, bars: usize) -> Self {
+        self.retention = Some(bars.max(1));
+        self
+    }
+
+    pub fn interval(&self) -> Option<Interval> {
+        self.interval
+    }
+
     pub fn insert(&mut self, kline: Kline) {
-        self.data.insert(kline.time, kline.clone());
-        self.calculate_indicators(&kline);
+        for timeframe in self.timeframes.values_mut() {
+            for bar in timeframe.resampler.push(kline.clone()) {
+                timeframe.history.insert(bar);
+            }
+        }
+
+        match self.bars.insert(kline) {
+            Placement::Appended => {
+                for values in self.indicators.values_mut() {
+                    values.push(None);
+                }
+                self.calculate_indicators(self.bars.len() - 1, false);
+                self.retain();
+            }
+            Placement::Replaced(index) if index + 1 == self.bars.len() => self.calculate_indicators(index, true),
+            Placement::Replaced(_) => self.recalculate_indicators(),
+            Placement::Inserted(index) => {
+                for values in self.indicators.values_mut() {
+                    values.insert(index, None);
+                }
+                self.recalculate_indicators();
+            }
+        }
+    }
+
+    fn retain(&mut self) {
+        let Some(retention) = self.retention else {
+            return;
+        };
+
+        if self.bars.len() >= 2 * retention {
+            let count = self.bars.len() - retention;
+
+            self.bars.drain_front(count);
+            self.offset += count;
+            for values in self.indicators.values_mut() {
+                values.drain(..count);
+            }
+        }
+    }
+
+    // Keeps a higher timeframe of the same symbol alongside this history,
+    // built from the klines already stored and every kline inserted later.
+    // Requires the interval of this history to be known.
+    pub fn add_timeframe(&mut self, interval: Interval) -> &mut History {
+        let base = self.interval.expect("history interval is required to add timeframes");
+        let bars = &self.bars;
+        let retention = self.retention;
+
+        &mut self
+            .timeframes
+            .entry(interval)
+            .or_insert_with(|| {
+                let mut resampler = Resampler::new(base, interval);
+                let mut history = History::new().with_interval(interval);
+                history.retention = retention;
+
+                for kline in bars.window(bars.len()).klines() {
+                    for bar in resampler.push(kline) {
+                        history.insert(bar);
+                    }
+                }
+
+                Timeframe { resampler, history }
+            })
+            .history
     }
 
-    fn calculate_indicators(&mut self, kline: &Kline) {
-        for (identifier, calculator) in &self.calculators {
-            let value = calculator.calculate(&self);
-            self.indicators
-                .entry(kline.time)
-                .or_insert_with(HashMap::new)
-                .insert(identifier.clone(), value);
+    pub fn timeframe(&self, interval: Interval) -> Option<&History> {
+        self.timeframes.get(&interval).map(|timeframe| &timeframe.history)
+    }
+
+    // The last bar of `interval` that had closed by the end of the bar of this
+    // history opened at `time`, so it never reflects later prices.
+    pub fn last_completed(&self, interval: Interval, time: DateTime<Utc>) -> Option<Kline> {
+        let (history, index) = self.last_completed_index(interval, time)?;
+
+        Some(history.bars.kline(index))
+    }
+
+    // Indicator values of `interval` up to the bar returned by `last_completed`.
+    pub fn last_completed_indicator_values(
+        &self,
+        interval: Interval,
+        indicator: &IndicatorIdentifier,
+        time: DateTime<Utc>,
+        count: usize,
+    ) -> Vec<Vec<f64>> {
+        match self.last_completed_index(interval, time) {
+            Some((history, index)) => history.indicator_values_until(indicator, index + 1, count),
+            None => Vec::new(),
+        }
+    }
+
+    fn last_completed_index(&self, interval: Interval, time: DateTime<Utc>) -> Option<(&History, usize)> {
+        let as_of = self.interval?.next(time);
+        let history = self.timeframe(interval)?;
+        let bars = history.window(history.len());
+        let end = bars.time.partition_point(|open| *open <= as_of);
+
+        (0..end)
+            .rev()
+            .find(|index| bars.closed[*index] && interval.next(bars.time[*index]) <= as_of)
+            .map(|index| (history, index))
+    }
+
+    // Indicators are advanced with the bar at `index`, the last one. Inputs are
+    // calculated before the indicators nested on them.
+    fn calculate_indicators(&mut self, index: usize, replace: bool) {
+        let mut calculators = std::mem::take(&mut self.calculators);
+        let kline = self.bars.kline(index);
+
+        let position = self.offset + index + 1;
+
+        for identifier in &self.order {
+            let input = calculators.get(identifier).and_then(|calculator| calculator.input());
+            let feed = Self::feed(&calculators, &self.indicators, input, index, &kline);
+            let Some(calculator) = calculators.get_mut(identifier) else {
+                continue;
+            };
+
+            let warm = position >= calculator.warmup();
+            let value = feed.map(|feed| calculator.incremental().update(&feed, replace));
+
+            if let Some(values) = self.indicators.get_mut(identifier) {
+                values[index] = value.filter(|_| warm);
+            }
+        }
+
+        self.calculators = calculators;
+    }
+
+    // Replays every bar through the indicators after a bar was inserted out of
+    // order.
+    fn recalculate_indicators(&mut self) {
+        for identifier in &self.order {
+            let Some(mut calculator) = self.calculators.remove(identifier) else {
+                continue;
+            };
+
+            calculator.incremental().reset();
+            let values = Self::replay(&self.bars, &self.calculators, &self.indicators, &mut calculator);
+            self.indicators.insert(identifier.clone(), values);
+
+            self.calculators.insert(identifier.clone(), calculator);
         }
     }
 
+    fn replay(
+        bars: &Bars,
+        calculators: &Calculators,
+        indicators: &HashMap<IndicatorIdentifier, Values>,
+        calculator: &mut Box<dyn Indicator>,
+    ) -> Values {
+        let window = bars.window(bars.len());
+        let warmup = calculator.warmup();
+        let input = calculator.input().cloned();
+        let incremental = calculator.incremental();
+
+        (0..window.len())
+            .map(|index| {
+                let feed = Self::feed(calculators, indicators, input.as_ref(), index, &window.kline(index))?;
+                Some(incremental.update(&feed, false)).filter(|_| index + 1 >= warmup)
+            })
+            .collect()
+    }
+
+    // The bar at `index` as seen by an indicator on `input`: the bar itself
+    // without input, otherwise a bar priced at the input's value, `None` while
+    // the input has no value.
+    fn feed(
+        calculators: &Calculators,
+        indicators: &HashMap<IndicatorIdentifier, Values>,
+        input: Option<&Input>,
+        index: usize,
+        kline: &Kline,
+    ) -> Option<Kline> {
+        let value = match input {
+            None => return Some(kline.clone()),
+            Some(Input::Volume) => kline.volume,
+            Some(Input::Indicator(indicator, output)) => {
+                let position = calculators
+                    .get(indicator.as_ref())?
+                    .outputs()
+                    .iter()
+                    .position(|name| name == output)?;
+
+                *indicators.get(indicator.as_ref())?[index].as_ref()?.get(position)?
+            }
+        };
+
+        Some(Kline {
+            open: value,
+            high: value,
+            low: value,
+            close: value,
+            ..kline.clone()
+        })
+    }
+
     pub fn len(&self) -> usize {
-        self.data.len()
+        self.bars.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.bars.is_empty()
+    }
+
+    pub fn get(&self, time: DateTime<Utc>) -> Option<Kline> {
+        self.bars.position(time).map(|index| self.bars.kline(index))
+    }
+
+    // Indicators requested after bars were inserted are caught up
+    // with the stored bars, so they hold values for the whole history. The
+    // inputs of nested indicators are requested along with them and shared
+    // with every other indicator using them.
     pub fn calculator(&mut self, indicator: &IndicatorIdentifier) -> &mut Box<dyn Indicator> {
+        if !self.calculators.contains_key(indicator) {
+            let mut calculator = indicators::factory::Factory::create(indicator);
+
+            if let Some(Input::Indicator(input, _)) = calculator.input() {
+                self.calculator(input);
+            }
+
+            let values = Self::replay(&self.bars, &self.calculators, &self.indicators, &mut calculator);
+            self.indicators.insert(indicator.clone(), values);
+            self.calculators.insert(indicator.clone(), calculator);
+            self.order.push(indicator.clone());
+        }
+
         self.calculators
-            .entry(indicator.clone())
-            .or_insert_with(|| indicators::factory::Factory::create(&indicator))
+            .get_mut(indicator)
+            .expect("calculator was just inserted")
     }
 
     pub fn request_calculators(&mut self, indicators: &[IndicatorIdentifier]) {
@@ -71,32 +330,116 @@ impl History {
         }
     }
 
+    // A view over the last `count` bars, borrowed from the history.
+    pub fn window(&self, count: usize) -> Window<'_> {
+        self.bars.window(count)
+    }
+
     pub fn last(&self, count: usize) -> Vec<Kline> {
-        let items = self.data.clone();
+        self.window(count).klines()
+    }
 
-        items
-            .into_iter()
-            .rev()
-            .take(count)
-            .rev()
-            .map(|(_, kline)| kline.clone())
-            .collect()
+    // Whether `indicator` has seen enough bars; values are only kept from then
+    // on. Bars dropped by the retention still count.
+    pub fn readiness(&self, indicator: &IndicatorIdentifier) -> Readiness {
+        match self.calculators.get(indicator) {
+            Some(calculator) => match calculator.warmup().saturating_sub(self.offset + self.len()) {
+                0 => Readiness::Ready,
+                remaining => Readiness::WarmingUp { remaining },
+            },
+            None => Readiness::Unavailable,
+        }
     }
 
-    pub fn get_indicator_values(&self, indicator: &IndicatorIdentifier, count: usize) -> Vec<Vec<f64>> {
-        self.indicators
+    pub fn is_ready(&self, indicators: &[IndicatorIdentifier]) -> bool {
+        indicators
             .iter()
-            .rev()
-            .take(count)
-            .rev()
-            .filter_map(|(_, indicators)| indicators.get(indicator).cloned())
+            .all(|indicator| self.readiness(indicator) == Readiness::Ready)
+    }
+
+    pub fn get_indicator_values(&self, indicator: &IndicatorIdentifier, count: usize) -> Vec<Vec<f64>> {
+        self.indicator_values_until(indicator, self.len(), count)
+    }
+
+    // One named output of a multi-output indicator, e.g. the signal line of
+    // MACD, for the last `count` bars.
+    pub fn get_indicator_output(&self, indicator: &IndicatorIdentifier, output: &str, count: usize) -> Vec<f64> {
+        let position = self
+            .calculators
+            .get(indicator)
+            .and_then(|calculator| calculator.outputs().iter().position(|name| *name == output));
+
+        match position {
+            Some(position) => self
+                .get_indicator_values(indicator, count)
+                .iter()
+                .filter_map(|values| values.get(position).copied())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // One output as plotted on the last `count` bars, for indicators that
+    // displace their outputs. A bar shows the value calculated `displacement`
+    // bars before it, so the leading spans of the last bar are known, while
+    // outputs displaced backwards are missing on the bars whose value needs
+    // later bars.
+    pub fn get_displaced_output(&self, indicator: &IndicatorIdentifier, output: &str, count: usize) -> Vec<f64> {
+        let Some((position, displacement)) = self.output_displacement(indicator, output) else {
+            return Vec::new();
+        };
+
+        (self.len().saturating_sub(count)..self.len())
+            .filter_map(|index| index.checked_add_signed(-displacement))
+            .filter_map(|index| self.output_at(indicator, index, position))
             .collect()
     }
+
+    // The values of an output displaced forwards plotted after the last bar,
+    // e.g. the cloud ahead of the price.
+    pub fn get_projected_output(&self, indicator: &IndicatorIdentifier, output: &str) -> Vec<f64> {
+        let Some((position, displacement)) = self.output_displacement(indicator, output) else {
+            return Vec::new();
+        };
+
+        (self.len().saturating_sub(displacement.max(0) as usize)..self.len())
+            .filter_map(|index| self.output_at(indicator, index, position))
+            .collect()
+    }
+
+    fn output_displacement(&self, indicator: &IndicatorIdentifier, output: &str) -> Option<(usize, isize)> {
+        let calculator = self.calculators.get(indicator)?;
+        let position = calculator.outputs().iter().position(|name| *name == output)?;
+
+        Some((position, calculator.displacement().get(position).copied().unwrap_or(0)))
+    }
+
+    fn output_at(&self, indicator: &IndicatorIdentifier, index: usize, position: usize) -> Option<f64> {
+        self.indicators
+            .get(indicator)?
+            .get(index)?
+            .as_ref()?
+            .get(position)
+            .copied()
+    }
+
+    fn indicator_values_until(&self, indicator: &IndicatorIdentifier, end: usize, count: usize) -> Vec<Vec<f64>> {
+        match self.indicators.get(indicator) {
+            Some(values) => values[end.saturating_sub(count)..end]
+                .iter()
+                .filter_map(|value| value.clone())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::data_structures::kline::helpers::generate_klines;
+    use chrono::TimeZone;
+
+    use crate::data_structures::kline::helpers::{generate_klines, generate_klines_with_interval};
+    use crate::indicators::{ema::EMAParams, price::PriceSource};
 
     use super::*;
 
@@ -118,7 +461,7 @@ mod tests {
         assert_eq!(history.len(), 5);
 
         for kline in klines {
-            assert_eq!(history.data.get(&kline.time), Some(&kline))
+            assert_eq!(history.get(kline.time), Some(kline))
         }
     }
 
@@ -136,4 +479,253 @@ mod tests {
 
         assert_eq!(last_5_klines, expected_klines);
     }
+
+    #[test]
+    fn test_insert_replaces_unfinished_kline() {
+        let mut history = History::new();
+        let klines = generate_klines(Utc::now(), 3);
+
+        for kline in klines.clone() {
+            history.insert(kline);
+        }
+
+        let mut partial = klines[2].clone();
+        partial.close = 105.0;
+        partial.closed = false;
+        history.insert(partial.clone());
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.last(1), vec![partial.clone()]);
+
+        let closed = Kline {
+            close: 106.0,
+            closed: true,
+            ..partial
+        };
+        history.insert(closed.clone());
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.last(1), vec![closed]);
+    }
+
+    fn minutes(count: usize) -> Vec<Kline> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let prices = (0..count).map(|i| 100.0 + i as f64).collect::<Vec<f64>>();
+
+        generate_klines_with_interval(start, &prices, 60)
+    }
+
+    #[test]
+    fn test_timeframes_follow_inserted_klines() {
+        let klines = minutes(12);
+        let mut history = History::new().with_interval(Interval::Minutes(1));
+        history.add_timeframe(Interval::Minutes(5));
+
+        for kline in klines.clone() {
+            history.insert(kline);
+        }
+
+        let bars = history.timeframe(Interval::Minutes(5)).unwrap().last(10);
+        assert_eq!(bars.len(), 3);
+        assert_eq!(
+            bars.iter().map(|bar| bar.closed).collect::<Vec<_>>(),
+            vec![true, true, false]
+        );
+        assert_eq!(bars[1].open, 105.0);
+        assert_eq!(bars[1].close, 109.0);
+        assert_eq!(bars[2].close, 111.0);
+    }
+
+    #[test]
+    fn test_added_timeframe_is_built_from_stored_klines() {
+        let klines = minutes(10);
+        let mut history = History::new().with_interval(Interval::Minutes(1));
+
+        for kline in klines[..7].iter().cloned() {
+            history.insert(kline);
+        }
+        history.add_timeframe(Interval::Minutes(5));
+        for kline in klines[7..].iter().cloned() {
+            history.insert(kline);
+        }
+
+        let bars = history.timeframe(Interval::Minutes(5)).unwrap().last(10);
+        assert_eq!(bars.len(), 2);
+        assert!(bars.iter().all(|bar| bar.closed));
+        assert_eq!(bars[1].open, 105.0);
+    }
+
+    #[test]
+    fn test_last_completed_has_no_lookahead() {
+        let klines = minutes(12);
+        let mut history = History::new().with_interval(Interval::Minutes(1));
+        history.add_timeframe(Interval::Minutes(5));
+
+        for kline in klines.clone() {
+            history.insert(kline);
+        }
+
+        let completed = |i: usize| {
+            history
+                .last_completed(Interval::Minutes(5), klines[i].time)
+                .map(|bar| bar.time)
+        };
+
+        assert_eq!(completed(3), None);
+        assert_eq!(completed(4), Some(klines[0].time));
+        assert_eq!(completed(8), Some(klines[0].time));
+        assert_eq!(completed(9), Some(klines[5].time));
+        // the 00:10 bar is still open at 00:11
+        assert_eq!(completed(11), Some(klines[5].time));
+    }
+
+    #[test]
+    fn test_indicators_are_requested_per_timeframe() {
+        let klines = minutes(30);
+        let ema = IndicatorIdentifier::EMA(EMAParams {
+            period: 2,
+            source: PriceSource::Close,
+        });
+        let mut history = History::new().with_interval(Interval::Minutes(1));
+        history
+            .add_timeframe(Interval::Minutes(5))
+            .request_calculators(std::slice::from_ref(&ema));
+
+        for kline in klines.clone() {
+            history.insert(kline);
+        }
+
+        assert!(history.get_indicator_values(&ema, 1).is_empty());
+        assert_eq!(
+            history
+                .timeframe(Interval::Minutes(5))
+                .unwrap()
+                .get_indicator_values(&ema, 10)
+                .len(),
+            5
+        );
+
+        let values = history.last_completed_indicator_values(Interval::Minutes(5), &ema, klines[12].time, 10);
+        assert_eq!(values.len(), 1);
+    }
+
+    #[test]
+    fn test_indicators_warm_up() {
+        let klines = minutes(6);
+        let rsi = IndicatorIdentifier::RSI(crate::indicators::rsi::RSIParams { period: 3 });
+        let ema = IndicatorIdentifier::EMA(EMAParams {
+            period: 2,
+            source: PriceSource::Close,
+        });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&rsi));
+
+        assert_eq!(history.readiness(&rsi), Readiness::WarmingUp { remaining: 4 });
+        assert_eq!(history.readiness(&ema), Readiness::Unavailable);
+
+        for kline in klines[..3].iter().cloned() {
+            history.insert(kline);
+        }
+
+        assert_eq!(history.readiness(&rsi), Readiness::WarmingUp { remaining: 1 });
+        assert!(history.get_indicator_values(&rsi, 10).is_empty());
+
+        for kline in klines[3..].iter().cloned() {
+            history.insert(kline);
+        }
+        history.request_calculators(std::slice::from_ref(&ema));
+
+        assert_eq!(history.readiness(&rsi), Readiness::Ready);
+        assert!(history.is_ready(&[rsi.clone(), ema.clone()]));
+        assert_eq!(history.get_indicator_values(&rsi, 10).len(), 3);
+        assert_eq!(history.get_indicator_values(&ema, 10).len(), 5);
+    }
+
+    #[test]
+    fn test_retention_bounds_history() {
+        let klines = minutes(100);
+        let ema = IndicatorIdentifier::EMA(EMAParams {
+            period: 3,
+            source: PriceSource::Close,
+        });
+        let mut history = History::new().with_retention(10);
+        history.request_calculators(std::slice::from_ref(&ema));
+
+        for kline in klines.clone() {
+            history.insert(kline);
+            assert!(history.len() < 20);
+        }
+
+        assert_eq!(history.last(10), klines[90..].to_vec());
+        assert_eq!(history.window(5).close, &[195.0, 196.0, 197.0, 198.0, 199.0]);
+        assert_eq!(history.get_indicator_values(&ema, 10).len(), 10);
+        assert_eq!(history.get(klines[0].time), None);
+    }
+
+    #[test]
+    fn test_nested_indicators_share_inputs() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let prices = (0..30).map(|i| 100.0 + (i * 7 % 11) as f64).collect::<Vec<f64>>();
+        let klines = generate_klines_with_interval(start, &prices, 60);
+        let rsi = IndicatorIdentifier::RSI(crate::indicators::rsi::RSIParams { period: 3 });
+        let ema = IndicatorIdentifier::EMA(EMAParams {
+            period: 2,
+            source: PriceSource::Close,
+        });
+        let ema_of_rsi = ema.clone().on(Input::Indicator(Box::new(rsi.clone()), "value"));
+        let ema_of_ema_of_rsi = ema.on(Input::Indicator(Box::new(ema_of_rsi.clone()), "value"));
+
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&ema_of_ema_of_rsi));
+        history.request_calculators(&[rsi.clone(), ema_of_rsi.clone()]);
+
+        assert_eq!(history.calculators.len(), 3);
+        assert!(history.order == vec![rsi, ema_of_rsi, ema_of_ema_of_rsi.clone()]);
+
+        // the fifth bar arrives last and every indicator is recalculated
+        let mut shuffled = klines.clone();
+        let late = shuffled.remove(4);
+        shuffled.push(late);
+        let mut reordered = History::new();
+        reordered.request_calculators(std::slice::from_ref(&ema_of_ema_of_rsi));
+
+        for kline in klines {
+            history.insert(kline);
+        }
+        for kline in shuffled {
+            reordered.insert(kline);
+        }
+
+        assert_eq!(history.readiness(&ema_of_ema_of_rsi), Readiness::Ready);
+        assert_eq!(history.get_indicator_values(&ema_of_ema_of_rsi, 30).len(), 30 - 5);
+        assert_eq!(
+            reordered.get_indicator_values(&ema_of_ema_of_rsi, 30),
+            history.get_indicator_values(&ema_of_ema_of_rsi, 30)
+        );
+    }
+
+    // cargo test --release -- --ignored test_long_backtest
+    // Inserting has to stay linear, a quadratic history takes minutes here.
+    #[test]
+    #[ignore]
+    fn test_long_backtest() {
+        let prices = (0..500_000).map(|i| 100.0 + (i % 97) as f64).collect::<Vec<f64>>();
+        let klines = generate_klines_with_interval(Utc::now(), &prices, 60);
+        let mut history = History::new();
+        history.request_calculators(&[
+            IndicatorIdentifier::EMA(EMAParams {
+                period: 200,
+                source: PriceSource::Close,
+            }),
+            IndicatorIdentifier::RSI(crate::indicators::rsi::RSIParams { period: 14 }),
+        ]);
+
+        let start = std::time::Instant::now();
+        for kline in klines {
+            history.insert(kline);
+        }
+
+        assert_eq!(history.len(), 500_000);
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
 }
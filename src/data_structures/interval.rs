@@ -0,0 +1,187 @@
+use core::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Months, TimeDelta, TimeZone, Utc};
+
+// Binance weekly bars open on Monday; 1970-01-05 is the first Monday after the
+// epoch.
+const FIRST_MONDAY: i64 = 4 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interval {
+    Seconds(u32),
+    Minutes(u32),
+    Hours(u32),
+    Days(u32),
+    Weeks(u32),
+    Months(u32),
+}
+
+impl Interval {
+    // Open time of the bar following the one opened at `time`.
+    pub fn next(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Interval::Months(count) => time
+                .checked_add_months(Months::new(*count))
+                .expect("interval out of range"),
+            _ => time + self.duration(),
+        }
+    }
+
+    // Open time of the calendar-aligned bar containing `time`.
+    pub fn bucket_start(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Interval::Months(count) => {
+                let months = (time.year() as i64 - 1970) * 12 + time.month0() as i64;
+                let start = months - months.rem_euclid(*count as i64);
+
+                Utc.with_ymd_and_hms(
+                    1970 + start.div_euclid(12) as i32,
+                    start.rem_euclid(12) as u32 + 1,
+                    1,
+                    0,
+                    0,
+                    0,
+                )
+                .single()
+                .expect("interval out of range")
+            }
+            _ => {
+                let origin = match self {
+                    Interval::Weeks(_) => FIRST_MONDAY,
+                    _ => 0,
+                };
+                let length = self.duration().num_seconds();
+                let seconds = time.timestamp() - origin;
+                let start = seconds - seconds.rem_euclid(length) + origin;
+
+                DateTime::from_timestamp(start, 0).expect("interval out of range")
+            }
+        }
+    }
+
+    // Length of one bar; months are approximated as 30 days.
+    pub fn duration(&self) -> TimeDelta {
+        match *self {
+            Interval::Seconds(count) => TimeDelta::seconds(count as i64),
+            Interval::Minutes(count) => TimeDelta::minutes(count as i64),
+            Interval::Hours(count) => TimeDelta::hours(count as i64),
+            Interval::Days(count) => TimeDelta::days(count as i64),
+            Interval::Weeks(count) => TimeDelta::weeks(count as i64),
+            Interval::Months(count) => TimeDelta::days(30 * count as i64),
+        }
+    }
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid interval: {}", s);
+        let (split, _) = s.char_indices().last().ok_or_else(error)?;
+        let (count, unit) = s.split_at(split);
+        let count: u32 = count.parse().map_err(|_| error())?;
+
+        if count == 0 {
+            return Err(error());
+        }
+
+        match unit {
+            "s" => Ok(Interval::Seconds(count)),
+            "m" => Ok(Interval::Minutes(count)),
+            "h" => Ok(Interval::Hours(count)),
+            "d" => Ok(Interval::Days(count)),
+            "w" => Ok(Interval::Weeks(count)),
+            "M" => Ok(Interval::Months(count)),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Interval::Seconds(count) => write!(f, "{}s", count),
+            Interval::Minutes(count) => write!(f, "{}m", count),
+            Interval::Hours(count) => write!(f, "{}h", count),
+            Interval::Days(count) => write!(f, "{}d", count),
+            Interval::Weeks(count) => write!(f, "{}w", count),
+            Interval::Months(count) => write!(f, "{}M", count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_binance_intervals() {
+        assert_eq!("1m".parse(), Ok(Interval::Minutes(1)));
+        assert_eq!("15m".parse(), Ok(Interval::Minutes(15)));
+        assert_eq!("4h".parse(), Ok(Interval::Hours(4)));
+        assert_eq!("1d".parse(), Ok(Interval::Days(1)));
+        assert_eq!("1w".parse(), Ok(Interval::Weeks(1)));
+        assert_eq!("1M".parse(), Ok(Interval::Months(1)));
+        assert_eq!(Interval::Hours(4).to_string(), "4h");
+    }
+
+    #[test]
+    fn test_parse_invalid_intervals() {
+        assert!("".parse::<Interval>().is_err());
+        assert!("m".parse::<Interval>().is_err());
+        assert!("0m".parse::<Interval>().is_err());
+        assert!("5y".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn test_parse_multibyte_unit() {
+        assert!("5µ".parse::<Interval>().is_err());
+        assert!("µ".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn test_bucket_start_is_calendar_aligned() {
+        let time = Utc.with_ymd_and_hms(2024, 5, 16, 13, 47, 31).unwrap();
+
+        assert_eq!(
+            Interval::Minutes(15).bucket_start(time),
+            Utc.with_ymd_and_hms(2024, 5, 16, 13, 45, 0).unwrap()
+        );
+        assert_eq!(
+            Interval::Hours(4).bucket_start(time),
+            Utc.with_ymd_and_hms(2024, 5, 16, 12, 0, 0).unwrap()
+        );
+        assert_eq!(
+            Interval::Days(1).bucket_start(time),
+            Utc.with_ymd_and_hms(2024, 5, 16, 0, 0, 0).unwrap()
+        );
+        // a Thursday, so the week opened on Monday the 13th
+        assert_eq!(
+            Interval::Weeks(1).bucket_start(time),
+            Utc.with_ymd_and_hms(2024, 5, 13, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            Interval::Months(1).bucket_start(time),
+            Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            Interval::Months(3).bucket_start(time),
+            Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_next_bar() {
+        let time = Utc.with_ymd_and_hms(2024, 1, 31, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            Interval::Minutes(5).next(time),
+            Utc.with_ymd_and_hms(2024, 1, 31, 0, 5, 0).unwrap()
+        );
+        assert_eq!(
+            Interval::Months(1).next(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+            Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap()
+        );
+    }
+}
@@ -9,6 +9,7 @@ pub struct Kline {
     pub low: f64,
     pub high: f64,
     pub volume: f64,
+    pub closed: bool,
 }
 
 impl Kline {}
@@ -23,6 +24,7 @@ impl Default for Kline {
             low: Default::default(),
             high: Default::default(),
             volume: Default::default(),
+            closed: true,
         }
     }
 }
@@ -1,6 +1,10 @@
+pub mod bars;
 pub mod history;
+pub mod interval;
 pub mod kline;
 pub mod performance_metrics;
 pub mod position;
+pub mod resampler;
 pub mod signal;
 pub mod trade;
+pub mod universe;
@@ -0,0 +1,276 @@
+use std::{collections::BTreeMap, collections::VecDeque};
+
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt};
+
+use super::{interval::Interval, kline::Kline};
+
+// Aggregates klines of one timeframe into calendar-aligned bars of a higher
+// one. Lower bars may be pushed repeatedly while they are unfinished; the
+// latest version of a bar replaces the previous one.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    from: Interval,
+    to: Interval,
+    bucket: Option<DateTime<Utc>>,
+    bars: BTreeMap<DateTime<Utc>, Kline>,
+}
+
+impl Resampler {
+    pub fn new(from: Interval, to: Interval) -> Self {
+        Self {
+            from,
+            to,
+            bucket: None,
+            bars: BTreeMap::new(),
+        }
+    }
+
+    pub fn interval(&self) -> Interval {
+        self.to
+    }
+
+    // Returns the higher timeframe bars affected by `kline`: the previous bar
+    // once it is complete, followed by the current state of the bar `kline`
+    // falls into. Bars older than the current bucket are ignored.
+    pub fn push(&mut self, kline: Kline) -> Vec<Kline> {
+        let bucket = self.to.bucket_start(kline.time);
+        let mut bars = Vec::new();
+
+        match self.bucket {
+            Some(current) if bucket < current => return bars,
+            Some(current) if bucket > current => bars.extend(self.flush(true)),
+            _ => {}
+        }
+
+        let complete = kline.closed && self.from.next(kline.time) >= self.to.next(bucket);
+
+        self.bucket = Some(bucket);
+        self.bars.insert(kline.time, kline);
+
+        match complete {
+            true => bars.extend(self.flush(true)),
+            false => bars.extend(self.aggregate(false)),
+        }
+
+        bars
+    }
+
+    // Returns the bar still being built, marked as unfinished.
+    pub fn finish(&mut self) -> Option<Kline> {
+        self.flush(false)
+    }
+
+    fn flush(&mut self, closed: bool) -> Option<Kline> {
+        let bar = self.aggregate(closed);
+        self.bars.clear();
+        bar
+    }
+
+    fn aggregate(&self, closed: bool) -> Option<Kline> {
+        let first = self.bars.values().next()?;
+        let last = self.bars.values().next_back()?;
+
+        Some(Kline {
+            time: self.bucket?,
+            symbol: first.symbol.clone(),
+            open: first.open,
+            close: last.close,
+            high: self.bars.values().map(|k| k.high).fold(f64::MIN, f64::max),
+            low: self.bars.values().map(|k| k.low).fold(f64::MAX, f64::min),
+            volume: self.bars.values().map(|k| k.volume).sum(),
+            closed,
+        })
+    }
+}
+
+// Resamples a series of closed klines. A trailing bucket that is not covered
+// until its end is returned as an unfinished bar.
+pub fn resample(klines: &[Kline], from: Interval, to: Interval) -> Vec<Kline> {
+    let mut resampler = Resampler::new(from, to);
+    let mut bars = klines
+        .iter()
+        .flat_map(|kline| resampler.push(kline.clone()))
+        .filter(|bar| bar.closed)
+        .collect::<Vec<Kline>>();
+
+    bars.extend(resampler.finish());
+    bars
+}
+
+// Adapts a live kline stream into a stream of higher timeframe bars, emitting
+// an unfinished bar on every update and a closed one when the bucket ends.
+pub fn resample_stream<S, E>(live: S, from: Interval, to: Interval) -> impl Stream<Item = Result<Kline, E>>
+where
+    S: Stream<Item = Result<Kline, E>>,
+{
+    resample_items(live, from, &[to], false).map(|item| item.map(|(_, kline)| kline))
+}
+
+// Feeds one stream into several timeframes, tagging every bar with the
+// timeframe it belongs to. Source bars are passed through first, tagged with
+// `from`, so a single subscription can drive every timeframe.
+pub fn resample_stream_many<S, E>(
+    live: S,
+    from: Interval,
+    to: &[Interval],
+) -> impl Stream<Item = Result<(Interval, Kline), E>>
+where
+    S: Stream<Item = Result<Kline, E>>,
+{
+    resample_items(live, from, to, true)
+}
+
+fn resample_items<S, E>(
+    live: S,
+    from: Interval,
+    to: &[Interval],
+    passthrough: bool,
+) -> impl Stream<Item = Result<(Interval, Kline), E>>
+where
+    S: Stream<Item = Result<Kline, E>>,
+{
+    let resamplers = to
+        .iter()
+        .map(|interval| Resampler::new(from, *interval))
+        .collect::<Vec<Resampler>>();
+
+    live.scan(resamplers, move |resamplers, item| {
+        let items = match item {
+            Ok(kline) => {
+                let mut bars = VecDeque::new();
+                if passthrough {
+                    bars.push_back(Ok((from, kline.clone())));
+                }
+                for resampler in resamplers.iter_mut() {
+                    let interval = resampler.interval();
+                    bars.extend(resampler.push(kline.clone()).into_iter().map(|bar| Ok((interval, bar))));
+                }
+                bars
+            }
+            Err(e) => VecDeque::from([Err(e)]),
+        };
+
+        futures::future::ready(Some(stream::iter(items)))
+    })
+    .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone, Utc};
+    use futures::{stream, StreamExt};
+
+    use crate::data_structures::{interval::Interval, kline::Kline};
+
+    use super::{resample, resample_stream, resample_stream_many};
+
+    fn minutes(count: usize) -> Vec<Kline> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        (0..count)
+            .map(|i| Kline {
+                time: start + TimeDelta::minutes(i as i64),
+                open: 100.0 + i as f64,
+                close: 101.0 + i as f64,
+                high: 102.0 + i as f64 + (i % 5) as f64,
+                low: 99.0 + i as f64 - (i % 3) as f64,
+                volume: 1.0 + i as f64,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ohlcv_aggregation() {
+        let klines = minutes(10);
+
+        let bars = resample(&klines, Interval::Minutes(1), Interval::Minutes(5));
+
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].time, klines[0].time);
+        assert_eq!(bars[0].open, 100.0);
+        assert_eq!(bars[0].close, 105.0);
+        assert_eq!(bars[0].high, 110.0);
+        assert_eq!(bars[0].low, 99.0);
+        assert_eq!(bars[0].volume, 15.0);
+        assert_eq!(bars[1].time, klines[5].time);
+        assert_eq!(bars[1].open, 105.0);
+        assert_eq!(bars[1].volume, 40.0);
+        assert!(bars.iter().all(|bar| bar.closed));
+    }
+
+    #[test]
+    fn test_buckets_are_calendar_aligned() {
+        // starts at 00:03, so the first 5m bucket only holds two minutes
+        let klines = minutes(12).split_off(3);
+
+        let bars = resample(&klines, Interval::Minutes(1), Interval::Minutes(5));
+
+        let times = bars.iter().map(|bar| bar.time).collect::<Vec<_>>();
+        assert_eq!(
+            times,
+            vec![klines[0].time - TimeDelta::minutes(3), klines[2].time, klines[7].time]
+        );
+        assert_eq!(bars[0].volume, 4.0 + 5.0);
+    }
+
+    #[test]
+    fn test_partial_final_bucket_is_unfinished() {
+        let klines = minutes(8);
+
+        let bars = resample(&klines, Interval::Minutes(1), Interval::Minutes(5));
+
+        assert_eq!(bars.len(), 2);
+        assert!(bars[0].closed);
+        assert!(!bars[1].closed);
+        assert_eq!(bars[1].close, 108.0);
+    }
+
+    #[tokio::test]
+    async fn test_stream_replaces_unfinished_bars() {
+        let klines = minutes(5);
+        let mut live = Vec::new();
+        for kline in klines {
+            live.push(Ok::<_, ()>(Kline {
+                close: kline.open,
+                closed: false,
+                ..kline.clone()
+            }));
+            live.push(Ok(kline));
+        }
+
+        let bars = resample_stream(stream::iter(live), Interval::Minutes(1), Interval::Minutes(5))
+            .map(|bar| bar.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(bars.len(), 10);
+        assert!(bars[..9].iter().all(|bar| !bar.closed));
+        assert_eq!(bars[8].close, 104.0);
+        assert!(bars[9].closed);
+        assert_eq!(bars[9].close, 105.0);
+        assert_eq!(bars[9].volume, 15.0);
+    }
+
+    #[tokio::test]
+    async fn test_one_stream_feeds_several_timeframes() {
+        let live = minutes(15).into_iter().map(Ok::<_, ()>);
+
+        let bars = resample_stream_many(
+            stream::iter(live),
+            Interval::Minutes(1),
+            &[Interval::Minutes(5), Interval::Minutes(15)],
+        )
+        .map(|bar| bar.unwrap())
+        .filter(|(_, bar)| futures::future::ready(bar.closed))
+        .collect::<Vec<_>>()
+        .await;
+
+        let count = |interval| bars.iter().filter(|(i, _)| *i == interval).count();
+        assert_eq!(count(Interval::Minutes(1)), 15);
+        assert_eq!(count(Interval::Minutes(5)), 3);
+        assert_eq!(count(Interval::Minutes(15)), 1);
+        assert_eq!(bars.last().unwrap().1.volume, (1..=15).sum::<usize>() as f64);
+    }
+}
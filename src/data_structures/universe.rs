@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use super::{history::History, kline::Kline};
+
+// The histories of several symbols, kept in step by the portfolio processor
+// so every history holds the same bars when strategies look at them.
+#[derive(Debug, Default)]
+pub struct Universe {
+    histories: BTreeMap<String, History>,
+    time: Option<DateTime<Utc>>,
+}
+
+impl Universe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, symbol: &str, history: History) {
+        self.histories.insert(symbol.to_string(), history);
+    }
+
+    pub fn insert(&mut self, kline: Kline) {
+        self.time = self.time.max(Some(kline.time));
+        self.histories.entry(kline.symbol.clone()).or_default().insert(kline);
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.histories.keys().map(String::as_str)
+    }
+
+    pub fn history(&self, symbol: &str) -> Option<&History> {
+        self.histories.get(symbol)
+    }
+
+    pub fn history_mut(&mut self, symbol: &str) -> Option<&mut History> {
+        self.histories.get_mut(symbol)
+    }
+
+    // Open time of the latest bar inserted for any symbol.
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        self.time
+    }
+}
@@ -1,15 +1,20 @@
 use rand::Rng;
 
-use crate::strategies::{factory::StrategyIdentifier, rsi_strategy::RSIStrategyParams};
-
-use super::strategies::crossover::EMACrossoverStrategyParams;
+use crate::strategies::{
+    bollinger_reversion::BollingerReversionStrategyParams, bollinger_squeeze::BollingerSqueezeStrategyParams,
+    crossover::EMACrossoverStrategyParams, dual_crossover::DualCrossOverStrategyParams, factory::StrategyIdentifier,
+    rsi_strategy::RSIStrategyParams,
+};
 
 impl StrategyIdentifier {
     pub fn random() -> Self {
-        let rng = rand::rng();
-        match rng.gen_range(0..2) {
+        let mut rng = rand::rng();
+        match rng.random_range(0..5) {
             0 => StrategyIdentifier::EMACrossoverStrategy(EMACrossoverStrategyParams::random()),
             1 => StrategyIdentifier::RSIStrategy(RSIStrategyParams::random()),
+            2 => StrategyIdentifier::DualCrossOver(DualCrossOverStrategyParams::random()),
+            3 => StrategyIdentifier::BollingerReversion(BollingerReversionStrategyParams::random()),
+            4 => StrategyIdentifier::BollingerSqueeze(BollingerSqueezeStrategyParams::random()),
             _ => unreachable!(),
         }
     }
@@ -0,0 +1,55 @@
+use rand::Rng;
+
+use crate::{
+    engines::generative::mutators::{mutate_f64, mutate_usize},
+    strategies::bollinger_squeeze::BollingerSqueezeStrategyParams,
+};
+
+const PERIOD_MIN: usize = 10;
+const PERIOD_MAX: usize = 50;
+const DEVIATIONS_MIN: f64 = 1.5;
+const DEVIATIONS_MAX: f64 = 3.0;
+const SQUEEZE_MIN: usize = 3;
+const SQUEEZE_MAX: usize = 20;
+const BANDWIDTH_MIN: f64 = 0.01;
+const BANDWIDTH_MAX: f64 = 0.1;
+
+impl BollingerSqueezeStrategyParams {
+    pub fn random() -> Self {
+        let mut rng = rand::rng();
+        Self {
+            period: rng.random_range(PERIOD_MIN..=PERIOD_MAX),
+            deviations: rng.random_range(DEVIATIONS_MIN..=DEVIATIONS_MAX),
+            squeeze: rng.random_range(SQUEEZE_MIN..=SQUEEZE_MAX),
+            max_bandwidth: rng.random_range(BANDWIDTH_MIN..=BANDWIDTH_MAX),
+        }
+    }
+
+    pub fn mutate(&mut self, mutation_rate: f64, mutation_strength: f64) {
+        let mut rng = rand::rng();
+        if rng.random_bool(mutation_rate) {
+            self.period = mutate_usize(self.period, PERIOD_MIN, PERIOD_MAX, mutation_strength, &mut rng);
+        }
+        if rng.random_bool(mutation_rate) {
+            self.deviations = mutate_f64(
+                self.deviations,
+                DEVIATIONS_MIN,
+                DEVIATIONS_MAX,
+                mutation_strength,
+                &mut rng,
+            );
+        }
+        if rng.random_bool(mutation_rate) {
+            self.squeeze = mutate_usize(self.squeeze, SQUEEZE_MIN, SQUEEZE_MAX, mutation_strength, &mut rng);
+        }
+        if rng.random_bool(mutation_rate) {
+            self.max_bandwidth = mutate_f64(
+                self.max_bandwidth,
+                BANDWIDTH_MIN,
+                BANDWIDTH_MAX,
+                mutation_strength,
+                &mut rng,
+            );
+        }
+    }
+}
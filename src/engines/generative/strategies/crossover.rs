@@ -1,8 +1,6 @@
 use rand::Rng;
 
-pub struct EMACrossoverStrategyParams {
-    period: usize,
-}
+use crate::{engines::generative::mutators::mutate_usize, strategies::crossover::EMACrossoverStrategyParams};
 
 const EMA_PERIOD_MIN: usize = 5;
 const EMA_PERIOD_MAX: usize = 50;
@@ -11,7 +9,14 @@ impl EMACrossoverStrategyParams {
     pub fn random() -> Self {
         let mut rng = rand::rng();
         Self {
-            period: rng.gen_range(EMA_PERIOD_MIN..=EMA_PERIOD_MAX),
+            period: rng.random_range(EMA_PERIOD_MIN..=EMA_PERIOD_MAX),
+        }
+    }
+
+    pub fn mutate(&mut self, mutation_rate: f64, mutation_strength: f64) {
+        let mut rng = rand::rng();
+        if rng.random_bool(mutation_rate) {
+            self.period = mutate_usize(self.period, EMA_PERIOD_MIN, EMA_PERIOD_MAX, mutation_strength, &mut rng);
         }
     }
 }
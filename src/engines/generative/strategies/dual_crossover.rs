@@ -0,0 +1,60 @@
+use rand::Rng;
+
+use crate::{
+    engines::generative::mutators::{mutate_f64, mutate_usize},
+    strategies::dual_crossover::{DualCrossOverStrategyParams, MovingAverage},
+};
+
+const FAST_PERIOD_MIN: usize = 3;
+const FAST_PERIOD_MAX: usize = 20;
+const SLOW_PERIOD_MIN: usize = 21;
+const SLOW_PERIOD_MAX: usize = 200;
+const CONFIRMATION_MAX: usize = 3;
+const SPREAD_MAX: f64 = 0.01;
+
+impl DualCrossOverStrategyParams {
+    pub fn random() -> Self {
+        let mut rng = rand::rng();
+        let averages = MovingAverage::ALL;
+
+        Self {
+            average: averages[rng.random_range(0..averages.len())],
+            fast_period: rng.random_range(FAST_PERIOD_MIN..=FAST_PERIOD_MAX),
+            slow_period: rng.random_range(SLOW_PERIOD_MIN..=SLOW_PERIOD_MAX),
+            confirmation: rng.random_range(0..=CONFIRMATION_MAX),
+            min_spread: rng.random_range(0.0..=SPREAD_MAX),
+        }
+    }
+
+    pub fn mutate(&mut self, mutation_rate: f64, mutation_strength: f64) {
+        let mut rng = rand::rng();
+        if rng.random_bool(mutation_rate) {
+            let averages = MovingAverage::ALL;
+            self.average = averages[rng.random_range(0..averages.len())];
+        }
+        if rng.random_bool(mutation_rate) {
+            self.fast_period = mutate_usize(
+                self.fast_period,
+                FAST_PERIOD_MIN,
+                FAST_PERIOD_MAX,
+                mutation_strength,
+                &mut rng,
+            );
+        }
+        if rng.random_bool(mutation_rate) {
+            self.slow_period = mutate_usize(
+                self.slow_period,
+                SLOW_PERIOD_MIN,
+                SLOW_PERIOD_MAX,
+                mutation_strength,
+                &mut rng,
+            );
+        }
+        if rng.random_bool(mutation_rate) {
+            self.confirmation = mutate_usize(self.confirmation, 0, CONFIRMATION_MAX, mutation_strength, &mut rng);
+        }
+        if rng.random_bool(mutation_rate) {
+            self.min_spread = mutate_f64(self.min_spread, 0.0, SPREAD_MAX, mutation_strength, &mut rng);
+        }
+    }
+}
@@ -1,2 +1,5 @@
-pub mod rsi;
+pub mod bollinger_reversion;
+pub mod bollinger_squeeze;
 pub mod crossover;
+pub mod dual_crossover;
+pub mod rsi;
@@ -13,8 +13,8 @@ const RSI_LEVEL_MAX: f64 = 40.0;
 impl RSIStrategyParams {
     pub fn random() -> Self {
         let mut rng = rand::rng();
-        let period = rng.gen_range(RSI_PERIOD_MIN..=RSI_PERIOD_MAX);
-        let oversold_level = rng.gen_range(RSI_LEVEL_MIN..=RSI_LEVEL_MAX);
+        let period = rng.random_range(RSI_PERIOD_MIN..=RSI_PERIOD_MAX);
+        let oversold_level = rng.random_range(RSI_LEVEL_MIN..=RSI_LEVEL_MAX);
         let overbought_level = 100.0 - oversold_level;
 
         Self {
@@ -26,25 +26,25 @@ impl RSIStrategyParams {
 
     pub fn mutate(&mut self, mutation_rate: f64, mutation_strength: f64) {
         let mut rng = rand::rng();
-        if rng.gen_bool(mutation_rate) {
-            self.period = mutate_usize(self.period, RSI_PERIOD_MIN, RSI_PERIOD_MAX, mutation_strength, rng);
+        if rng.random_bool(mutation_rate) {
+            self.period = mutate_usize(self.period, RSI_PERIOD_MIN, RSI_PERIOD_MAX, mutation_strength, &mut rng);
         }
-        if rng.gen_bool(mutation_rate) {
+        if rng.random_bool(mutation_rate) {
             self.oversold_level = mutate_f64(
                 self.oversold_level,
                 RSI_LEVEL_MIN,
                 RSI_LEVEL_MAX,
                 mutation_strength,
-                rng,
+                &mut rng,
             )
         }
-        if rng.gen_bool(mutation_rate) {
+        if rng.random_bool(mutation_rate) {
             self.overbought_level = mutate_f64(
                 self.overbought_level,
-                100 - RSI_LEVEL_MAX,
-                100 - RSI_LEVEL_MIN,
+                100.0 - RSI_LEVEL_MAX,
+                100.0 - RSI_LEVEL_MIN,
                 mutation_strength,
-                rng,
+                &mut rng,
             );
         }
     }
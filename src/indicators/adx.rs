@@ -0,0 +1,170 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{running::RunningWilder, series, tr::TR, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct ADXParams {
+    pub period: usize,
+}
+
+impl ADXParams {
+    pub fn name(&self) -> String {
+        format!("adx_{}", self.period)
+    }
+}
+
+// Wilder's directional movement system. +DI and -DI are the smoothed upward
+// and downward moves of the highs and lows relative to the true range, the
+// ADX the smoothed difference between them relative to their sum.
+#[allow(clippy::upper_case_acronyms)]
+pub struct ADX {
+    pub params: ADXParams,
+    // high and low of the bar before the last one, kept to replace the last
+    // bar
+    previous: Option<(f64, f64)>,
+    last: Option<(f64, f64)>,
+    range: TR,
+    average_range: RunningWilder,
+    plus: RunningWilder,
+    minus: RunningWilder,
+    adx: RunningWilder,
+}
+
+impl ADX {
+    pub const OUTPUTS: [&'static str; 3] = ["adx", "plus_di", "minus_di"];
+
+    pub fn new(params: ADXParams) -> Self {
+        ADX {
+            previous: None,
+            last: None,
+            range: TR::new(),
+            average_range: RunningWilder::new(params.period),
+            plus: RunningWilder::new(params.period),
+            minus: RunningWilder::new(params.period),
+            adx: RunningWilder::new(params.period),
+            params,
+        }
+    }
+}
+
+impl Indicator for ADX {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(ADX::new(self.params.clone()), history)
+    }
+
+    // `period` moves for the first DX and `period` DX values for the ADX
+    fn warmup(&self) -> usize {
+        2 * self.params.period
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for ADX {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        if !replace {
+            self.previous = self.last;
+        }
+        self.last = Some((kline.high, kline.low));
+
+        let range = self.range.range(kline, replace);
+        let Some((high, low)) = self.previous else {
+            return vec![0.0, 0.0, 0.0];
+        };
+
+        let (up, down) = (kline.high - high, low - kline.low);
+        let plus = if up > down && up > 0.0 { up } else { 0.0 };
+        let minus = if down > up && down > 0.0 { down } else { 0.0 };
+
+        let range = self.average_range.update(range, replace);
+        let plus = self.plus.update(plus, replace);
+        let minus = self.minus.update(minus, replace);
+        let (plus, minus) = match range > 0.0 {
+            true => (100.0 * plus / range, 100.0 * minus / range),
+            false => (0.0, 0.0),
+        };
+        let dx = match plus + minus > 0.0 {
+            true => 100.0 * (plus - minus).abs() / (plus + minus),
+            false => 0.0,
+        };
+
+        // the ADX starts with the first DX over a full period
+        let adx = match self.average_range.is_ready() {
+            true => self.adx.update(dx, replace),
+            false => dx,
+        };
+
+        vec![adx, plus, minus]
+    }
+
+    fn reset(&mut self) {
+        self.previous = None;
+        self.last = None;
+        IncrementalIndicator::reset(&mut self.range);
+        self.average_range.reset();
+        self.plus.reset();
+        self.minus.reset();
+        self.adx.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            factory::Factory,
+            helpers::{assert_output_reference, bars},
+            IndicatorIdentifier,
+        },
+    };
+
+    use super::ADXParams;
+
+    #[test]
+    fn test_adx_reference_values() {
+        let adx = IndicatorIdentifier::ADX(ADXParams { period: 5 });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&adx));
+
+        for kline in bars() {
+            history.insert(kline);
+        }
+
+        assert_eq!(Factory::create(&adx).warmup(), 10);
+        assert_output_reference(
+            &history,
+            &adx,
+            "adx",
+            &[
+                33.4974, 38.8062, 43.0531, 37.8846, 37.5579, 37.2966, 30.4073, 24.8959, 27.1288, 28.9151, 28.8269,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &adx,
+            "plus_di",
+            &[
+                28.0664, 23.3471, 19.5381, 15.1088, 16.7668, 14.6882, 11.8288, 10.1504, 17.6648, 14.4442, 10.6465,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &adx,
+            "minus_di",
+            &[
+                7.0076, 5.8293, 4.8782, 10.6719, 7.8448, 6.8723, 12.5228, 10.746, 8.3013, 6.7878, 19.1231,
+            ],
+        );
+    }
+}
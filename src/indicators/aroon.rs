@@ -0,0 +1,139 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{running::RunningWindow, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct AroonParams {
+    pub period: usize,
+}
+
+impl AroonParams {
+    pub fn name(&self) -> String {
+        format!("aroon_{}", self.period)
+    }
+}
+
+// How recently the highest high and the lowest low of the last `period` bars
+// were made, 100 for the current bar and 0 for `period` bars ago, and the
+// difference between both.
+pub struct Aroon {
+    pub params: AroonParams,
+    highs: RunningWindow,
+    lows: RunningWindow,
+}
+
+impl Aroon {
+    pub const OUTPUTS: [&'static str; 3] = ["up", "down", "oscillator"];
+
+    pub fn new(params: AroonParams) -> Self {
+        Aroon {
+            // the current bar and `period` bars before it
+            highs: RunningWindow::new(params.period + 1),
+            lows: RunningWindow::new(params.period + 1),
+            params,
+        }
+    }
+
+    // Percent of the period since the extreme preferred by `better`, the most
+    // recent one on a tie.
+    fn recency(&self, window: &RunningWindow, better: fn(f64, f64) -> bool) -> f64 {
+        let values = window.values();
+        let index = (0..values.len()).fold(0, |best, index| match better(values[best], values[index]) {
+            true => best,
+            false => index,
+        });
+        let since = values.len() - 1 - index;
+
+        100.0 * (self.params.period as f64 - since as f64) / self.params.period.max(1) as f64
+    }
+}
+
+impl Indicator for Aroon {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(Aroon::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period + 1
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for Aroon {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        self.highs.update(kline.high, replace);
+        self.lows.update(kline.low, replace);
+
+        let up = self.recency(&self.highs, |best, value| best > value);
+        let down = self.recency(&self.lows, |best, value| best < value);
+
+        vec![up, down, up - down]
+    }
+
+    fn reset(&mut self) {
+        self.highs.reset();
+        self.lows.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            factory::Factory,
+            helpers::{assert_output_reference, bars},
+            IndicatorIdentifier,
+        },
+    };
+
+    use super::AroonParams;
+
+    #[test]
+    fn test_aroon_reference_values() {
+        let aroon = IndicatorIdentifier::Aroon(AroonParams { period: 5 });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&aroon));
+
+        for kline in bars() {
+            history.insert(kline);
+        }
+
+        assert_eq!(Factory::create(&aroon).warmup(), 6);
+        assert_output_reference(
+            &history,
+            &aroon,
+            "up",
+            &[
+                100.0, 100.0, 100.0, 100.0, 100.0, 100.0, 80.0, 60.0, 100.0, 100.0, 100.0, 80.0, 100.0, 100.0, 80.0,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &aroon,
+            "down",
+            &[
+                80.0, 60.0, 40.0, 20.0, 0.0, 0.0, 0.0, 0.0, 0.0, 80.0, 60.0, 40.0, 20.0, 0.0, 100.0,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &aroon,
+            "oscillator",
+            &[
+                20.0, 40.0, 60.0, 80.0, 100.0, 100.0, 80.0, 60.0, 100.0, 20.0, 40.0, 40.0, 80.0, 100.0, -20.0,
+            ],
+        );
+    }
+}
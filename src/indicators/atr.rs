@@ -0,0 +1,111 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{running::RunningWilder, series, tr::TR, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct ATRParams {
+    pub period: usize,
+}
+
+impl ATRParams {
+    pub fn name(&self) -> String {
+        format!("atr_{}", self.period)
+    }
+}
+
+// The average true range with Wilder's smoothing.
+#[allow(clippy::upper_case_acronyms)]
+pub struct ATR {
+    pub params: ATRParams,
+    range: TR,
+    average: RunningWilder,
+}
+
+impl ATR {
+    pub fn new(params: ATRParams) -> Self {
+        ATR {
+            range: TR::new(),
+            average: RunningWilder::new(params.period),
+            params,
+        }
+    }
+
+    pub(super) fn value(&mut self, kline: &Kline, replace: bool) -> f64 {
+        let range = self.range.range(kline, replace);
+        self.average.update(range, replace)
+    }
+}
+
+impl Indicator for ATR {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(ATR::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for ATR {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        vec![self.value(kline, replace)]
+    }
+
+    fn reset(&mut self) {
+        IncrementalIndicator::reset(&mut self.range);
+        self.average.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{history::History, kline::Kline},
+        indicators::{
+            helpers::{assert_reference, bars},
+            IncrementalIndicator, Indicator,
+        },
+    };
+
+    use super::{ATRParams, ATR};
+
+    #[test]
+    fn test_atr_reference_values() {
+        let history = History::with_klines(bars());
+        let atr = ATR::new(ATRParams { period: 5 });
+
+        assert_reference(
+            &atr.calculate(&history),
+            atr.warmup(),
+            &[
+                0.814, 0.8512, 0.835, 0.832, 0.8496, 0.8277, 0.8001, 0.7681, 0.7985, 0.8728, 0.7982, 0.7946, 0.7417,
+                0.7693, 0.7535, 0.8188,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_atr_replaces_unfinished_bar() {
+        let klines = bars();
+        let expected = ATR::new(ATRParams { period: 5 }).calculate(&History::with_klines(klines.clone()));
+        let mut atr = ATR::new(ATRParams { period: 5 });
+
+        for (kline, expected) in klines.iter().zip(expected) {
+            let partial = Kline {
+                high: kline.high + 2.0,
+                ..kline.clone()
+            };
+            atr.update(&partial, false);
+
+            assert!((atr.update(kline, true)[0] - expected).abs() < 1e-9);
+        }
+    }
+}
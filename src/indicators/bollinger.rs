@@ -0,0 +1,191 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{factor::Factor, price::PriceSource, running::RunningVariance, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct BollingerParams {
+    pub period: usize,
+    // width of the bands in standard deviations
+    pub deviations: Factor,
+    pub source: PriceSource,
+}
+
+impl BollingerParams {
+    pub fn name(&self) -> String {
+        format!("bb_{}_{}{}", self.period, self.deviations, self.source.suffix())
+    }
+}
+
+// Bands a number of standard deviations around the simple average. Bandwidth
+// is their distance relative to the average and %B the position of the price
+// between them, 0 at the lower and 1 at the upper band. The bandwidth around an
+// average of 0 is 0.
+pub struct Bollinger {
+    pub params: BollingerParams,
+    prices: RunningVariance,
+}
+
+impl Bollinger {
+    pub const OUTPUTS: [&'static str; 5] = ["mid", "upper", "lower", "bandwidth", "percent_b"];
+
+    pub fn new(params: BollingerParams) -> Self {
+        Bollinger {
+            prices: RunningVariance::new(params.period),
+            params,
+        }
+    }
+}
+
+impl Indicator for Bollinger {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(Bollinger::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for Bollinger {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let price = self.params.source.value(kline);
+        let (mid, variance) = self.prices.update(price, replace);
+        let width = self.params.deviations.value() * variance.sqrt();
+
+        let (upper, lower) = (mid + width, mid - width);
+        let percent_b = match upper > lower {
+            true => (price - lower) / (upper - lower),
+            false => 0.5,
+        };
+
+        let bandwidth = match mid != 0.0 {
+            true => (upper - lower) / mid,
+            false => 0.0,
+        };
+
+        vec![mid, upper, lower, bandwidth, percent_b]
+    }
+
+    fn reset(&mut self) {
+        self.prices.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices},
+        indicators::{
+            factor::Factor,
+            factory::Factory,
+            helpers::{assert_output_reference, PRICES},
+            price::PriceSource,
+            IncrementalIndicator, IndicatorIdentifier,
+        },
+    };
+
+    use super::{Bollinger, BollingerParams};
+
+    #[test]
+    fn test_bollinger_reference_values() {
+        let bollinger = IndicatorIdentifier::Bollinger(BollingerParams {
+            period: 5,
+            deviations: Factor(2.0),
+            source: PriceSource::Close,
+        });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&bollinger));
+
+        for kline in generate_klines_with_prices(&PRICES) {
+            history.insert(kline);
+        }
+
+        assert_eq!(Factory::create(&bollinger).warmup(), 5);
+        assert_output_reference(
+            &history,
+            &bollinger,
+            "mid",
+            &[
+                44.104, 44.202, 44.404, 44.658, 45.104, 45.454, 45.666, 45.852, 45.89, 45.978, 46.018, 46.04, 46.04,
+                46.2, 46.188, 46.06,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &bollinger,
+            "upper",
+            &[
+                44.6355, 44.9902, 45.4495, 45.9265, 46.13, 46.3734, 46.3775, 46.3184, 46.2206, 46.423, 46.5242,
+                46.5314, 46.5314, 46.5172, 46.4966, 46.573,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &bollinger,
+            "lower",
+            &[
+                43.5725, 43.4138, 43.3585, 43.3895, 44.078, 44.5346, 44.9545, 45.3856, 45.5594, 45.533, 45.5118,
+                45.5486, 45.5486, 45.8828, 45.8794, 45.547,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &bollinger,
+            "bandwidth",
+            &[
+                0.0241, 0.0357, 0.0471, 0.0568, 0.0455, 0.0405, 0.0312, 0.0203, 0.0144, 0.0194, 0.022, 0.0213, 0.0213,
+                0.0137, 0.0134, 0.0223,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &bollinger,
+            "percent_b",
+            &[
+                0.7126, 0.8984, 0.8329, 0.8003, 0.8587, 0.8404, 0.6574, 0.6908, 0.0765, 0.8394, 0.7588, 0.4593, 0.4898,
+                0.831, 0.5518, 0.0907,
+            ],
+        );
+    }
+
+    fn bands(prices: &[f64]) -> Vec<f64> {
+        let mut bollinger = Bollinger::new(BollingerParams {
+            period: 5,
+            deviations: Factor(2.0),
+            source: PriceSource::Close,
+        });
+
+        generate_klines_with_prices(prices)
+            .iter()
+            .map(|kline| bollinger.update(kline, false))
+            .last()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_large_prices_keep_their_deviation() {
+        let values = bands(&[1e9 + 1.0, 1e9 + 2.0, 1e9 + 3.0, 1e9 + 4.0, 1e9 + 5.0]);
+
+        assert!((values[1] - values[2] - 8f64.sqrt() * 2.0).abs() < 1e-6);
+        assert!((values[4] - 0.5 - 1.0 / 8f64.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_zero_average_has_no_bandwidth() {
+        let values = bands(&[0.0; 5]);
+
+        assert_eq!(values, vec![0.0, 0.0, 0.0, 0.0, 0.5]);
+    }
+}
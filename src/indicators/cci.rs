@@ -0,0 +1,98 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{price::PriceSource, running::RunningWindow, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct CCIParams {
+    pub period: usize,
+}
+
+impl CCIParams {
+    pub fn name(&self) -> String {
+        format!("cci_{}", self.period)
+    }
+}
+
+// The commodity channel index: the distance of the typical price from its
+// average in units of 0.015 mean absolute deviations.
+#[allow(clippy::upper_case_acronyms)]
+pub struct CCI {
+    pub params: CCIParams,
+    prices: RunningWindow,
+}
+
+impl CCI {
+    pub fn new(params: CCIParams) -> Self {
+        CCI {
+            prices: RunningWindow::new(params.period),
+            params,
+        }
+    }
+}
+
+impl Indicator for CCI {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(CCI::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for CCI {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let price = PriceSource::HLC3.value(kline);
+        self.prices.update(price, replace);
+
+        let prices = self.prices.values();
+        let count = prices.len() as f64;
+        let mean = prices.iter().sum::<f64>() / count;
+        let deviation = prices.iter().map(|price| (price - mean).abs()).sum::<f64>() / count;
+
+        match deviation > 0.0 {
+            true => vec![(price - mean) / (0.015 * deviation)],
+            false => vec![0.0],
+        }
+    }
+
+    fn reset(&mut self) {
+        self.prices.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            helpers::{assert_reference, bars},
+            Indicator,
+        },
+    };
+
+    use super::{CCIParams, CCI};
+
+    #[test]
+    fn test_cci_reference_values() {
+        let history = History::with_klines(bars());
+        let cci = CCI::new(CCIParams { period: 5 });
+
+        assert_reference(
+            &cci.calculate(&history),
+            cci.warmup(),
+            &[
+                -7.4224, 165.328, 112.2222, 97.3684, 107.2389, 107.7156, 68.7932, 56.8627, -69.6572, 90.604, 140.1901,
+                30.4552, -10.7527, 84.3621, 56.2397, -124.2312,
+            ],
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{running::RunningSma, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct CMFParams {
+    pub period: usize,
+}
+
+impl CMFParams {
+    pub fn name(&self) -> String {
+        format!("cmf_{}", self.period)
+    }
+}
+
+// Chaikin money flow: the volume of the last `period` bars weighted by where
+// each bar closed within its range, from -1 at the low to 1 at the high,
+// relative to their total volume.
+#[allow(clippy::upper_case_acronyms)]
+pub struct CMF {
+    pub params: CMFParams,
+    flow: RunningSma,
+    volume: RunningSma,
+}
+
+impl CMF {
+    pub fn new(params: CMFParams) -> Self {
+        CMF {
+            flow: RunningSma::new(params.period),
+            volume: RunningSma::new(params.period),
+            params,
+        }
+    }
+}
+
+impl Indicator for CMF {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(CMF::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for CMF {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let range = kline.high - kline.low;
+        let multiplier = match range > 0.0 {
+            true => ((kline.close - kline.low) - (kline.high - kline.close)) / range,
+            false => 0.0,
+        };
+
+        // the ratio of the averages is the ratio of the sums
+        let flow = self.flow.update(multiplier * kline.volume, replace);
+        let volume = self.volume.update(kline.volume, replace);
+
+        match volume > 0.0 {
+            true => vec![flow / volume],
+            false => vec![0.0],
+        }
+    }
+
+    fn reset(&mut self) {
+        self.flow.reset();
+        self.volume.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            helpers::{assert_reference, bars},
+            Indicator,
+        },
+    };
+
+    use super::{CMFParams, CMF};
+
+    #[test]
+    fn test_cmf_reference_values() {
+        let history = History::with_klines(bars());
+        let cmf = CMF::new(CMFParams { period: 5 });
+
+        assert_reference(
+            &cmf.calculate(&history),
+            cmf.warmup(),
+            &[
+                -0.1479, -0.0479, 0.0781, 0.146, 0.3547, 0.2803, 0.1436, 0.1146, -0.0521, -0.0219, -0.1127, -0.1239,
+                -0.1596, 0.0171, -0.1795, -0.2798,
+            ],
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{price::PriceSource, running::RunningEma, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct DEMAParams {
+    pub period: usize,
+    pub source: PriceSource,
+}
+
+impl DEMAParams {
+    pub fn name(&self) -> String {
+        format!("dema_{}{}", self.period, self.source.suffix())
+    }
+}
+
+// Double EMA: 2 * EMA - EMA(EMA).
+#[allow(clippy::upper_case_acronyms)]
+pub struct DEMA {
+    pub params: DEMAParams,
+    ema: RunningEma,
+    ema_of_ema: RunningEma,
+}
+
+impl DEMA {
+    pub fn new(params: DEMAParams) -> Self {
+        assert!(params.period > 0, "DEMA period has to be positive");
+
+        DEMA {
+            ema: RunningEma::new(params.period),
+            ema_of_ema: RunningEma::new(params.period),
+            params,
+        }
+    }
+}
+
+impl Indicator for DEMA {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(DEMA::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        2 * self.params.period - 1
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for DEMA {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let price = self.params.source.value(kline);
+        let ema = self.ema.update(price, replace);
+        let ema_of_ema = self.ema_of_ema.update(ema, replace);
+
+        vec![2.0 * ema - ema_of_ema]
+    }
+
+    fn reset(&mut self) {
+        self.ema.reset();
+        self.ema_of_ema.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices},
+        indicators::{
+            helpers::{assert_reference, PRICES},
+            price::PriceSource,
+            Indicator,
+        },
+    };
+
+    use super::{DEMAParams, DEMA};
+
+    #[test]
+    fn test_dema_reference_values() {
+        let history = History::with_klines(generate_klines_with_prices(&PRICES));
+        let dema = DEMA::new(DEMAParams {
+            period: 5,
+            source: PriceSource::Close,
+        });
+
+        let values = dema.calculate(&history);
+
+        assert_reference(
+            &values,
+            dema.warmup(),
+            &[
+                45.6431, 45.9848, 46.0416, 46.1278, 45.922, 46.168, 46.2898, 46.1873, 46.1376, 46.3147, 46.2984,
+                45.9602,
+            ],
+        );
+    }
+}
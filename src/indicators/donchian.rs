@@ -0,0 +1,126 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{running::RunningWindow, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct DonchianParams {
+    pub period: usize,
+}
+
+impl DonchianParams {
+    pub fn name(&self) -> String {
+        format!("dc_{}", self.period)
+    }
+}
+
+// The highest high and lowest low of the last `period` bars and the middle
+// between them.
+pub struct Donchian {
+    pub params: DonchianParams,
+    highs: RunningWindow,
+    lows: RunningWindow,
+}
+
+impl Donchian {
+    pub const OUTPUTS: [&'static str; 3] = ["upper", "lower", "mid"];
+
+    pub fn new(params: DonchianParams) -> Self {
+        Donchian {
+            highs: RunningWindow::new(params.period),
+            lows: RunningWindow::new(params.period),
+            params,
+        }
+    }
+}
+
+impl Indicator for Donchian {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(Donchian::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for Donchian {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        self.highs.update(kline.high, replace);
+        self.lows.update(kline.low, replace);
+
+        let (upper, lower) = (self.highs.max(), self.lows.min());
+
+        vec![upper, lower, (upper + lower) / 2.0]
+    }
+
+    fn reset(&mut self) {
+        self.highs.reset();
+        self.lows.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            factory::Factory,
+            helpers::{assert_output_reference, bars},
+            IndicatorIdentifier,
+        },
+    };
+
+    use super::DonchianParams;
+
+    #[test]
+    fn test_donchian_reference_values() {
+        let donchian = IndicatorIdentifier::Donchian(DonchianParams { period: 5 });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&donchian));
+
+        for kline in bars() {
+            history.insert(kline);
+        }
+
+        assert_eq!(Factory::create(&donchian).warmup(), 5);
+        assert_output_reference(
+            &history,
+            &donchian,
+            "upper",
+            &[
+                44.64, 45.13, 45.4, 45.72, 46.14, 46.38, 46.38, 46.38, 46.38, 46.58, 46.58, 46.58, 46.58, 46.71, 46.71,
+                46.71,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &donchian,
+            "lower",
+            &[
+                43.41, 43.41, 43.41, 43.41, 43.41, 44.13, 44.63, 44.9, 45.22, 45.41, 45.41, 45.41, 45.41, 45.41, 45.8,
+                45.44,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &donchian,
+            "mid",
+            &[
+                44.025, 44.27, 44.405, 44.565, 44.775, 45.255, 45.505, 45.64, 45.8, 45.995, 45.995, 45.995, 45.995,
+                46.06, 46.255, 46.075,
+            ],
+        );
+    }
+}
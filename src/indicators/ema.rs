@@ -1,27 +1,30 @@
-use std::f64;
+use crate::data_structures::{history::History, kline::Kline};
 
-use crate::data_structures::history::History;
-
-use super::Indicator;
+use super::{price::PriceSource, running::RunningEma, series, IncrementalIndicator, Indicator};
 
 #[derive(PartialEq, Eq, Hash, Clone)]
 pub struct EMAParams {
     pub period: usize,
+    pub source: PriceSource,
 }
 
 impl EMAParams {
     pub fn name(&self) -> String {
-        format!("ema_{}", self.period)
+        format!("ema_{}{}", self.period, self.source.suffix())
     }
 }
 
 pub struct EMA {
     pub params: EMAParams,
+    ema: RunningEma,
 }
 
 impl EMA {
     pub fn new(params: EMAParams) -> Self {
-        EMA { params }
+        EMA {
+            ema: RunningEma::new(params.period),
+            params,
+        }
     }
 }
 
@@ -29,23 +32,28 @@ impl Indicator for EMA {
     fn name(&self) -> String {
         self.params.name()
     }
+
+    // Running EMA over the whole history, seeded with the first price.
     fn calculate(&self, history: &History) -> Vec<f64> {
-        let multiplier = 2.0 / (self.params.period as f64 + 1.0);
-        let mut ema_values = Vec::new();
-        let mut ema_prev: Option<f64> = None;
+        series(EMA::new(self.params.clone()), history)
+    }
 
-        for kline in history.last(self.params.period).iter() {
-            let close = kline.close;
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
 
-            ema_prev = Some(match ema_prev {
-                None => close,
-                Some(ema_prev) => ((close - ema_prev) * multiplier) + ema_prev,
-            });
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
 
-            ema_values.push(ema_prev.unwrap());
-        }
+impl IncrementalIndicator for EMA {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        vec![self.ema.update(self.params.source.value(kline), replace)]
+    }
 
-        ema_values
+    fn reset(&mut self) {
+        self.ema.reset();
     }
 }
 
@@ -53,8 +61,8 @@ impl Indicator for EMA {
 mod tests {
 
     use crate::{
-        data_structures::{history::History, kline::helpers::generate_klines_with_prices},
-        indicators::{ema::EMAParams, Indicator},
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices, kline::Kline},
+        indicators::{ema::EMAParams, price::PriceSource, IncrementalIndicator, Indicator, IndicatorIdentifier},
     };
 
     use super::EMA;
@@ -64,7 +72,10 @@ mod tests {
         let prices = vec![100.0, 105.0, 110.0];
         let history = History::with_klines(generate_klines_with_prices(&prices));
 
-        let ema = EMA::new(EMAParams { period: 3 });
+        let ema = EMA::new(EMAParams {
+            period: 3,
+            source: PriceSource::Close,
+        });
         let result = ema.calculate(&history);
         let expected = vec![100.0, 102.5, 106.25];
 
@@ -76,10 +87,51 @@ mod tests {
         let prices = vec![100.0, 105.0];
         let history = History::with_klines(generate_klines_with_prices(&prices));
 
-        let ema = EMA::new(EMAParams { period: 3 });
+        let ema = EMA::new(EMAParams {
+            period: 3,
+            source: PriceSource::Close,
+        });
         let result = ema.calculate(&history);
         let expected = vec![100.0, 102.5];
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_ema_is_not_reseeded_from_recent_bars() {
+        let prices = vec![100.0, 110.0, 120.0, 130.0, 140.0];
+        let ema = IndicatorIdentifier::EMA(EMAParams {
+            period: 3,
+            source: PriceSource::Close,
+        });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&ema));
+
+        for kline in generate_klines_with_prices(&prices) {
+            history.insert(kline);
+        }
+
+        // 100, 105, 112.5, 121.25, 130.625
+        assert_eq!(history.get_indicator_values(&ema, 1), vec![vec![130.625]]);
+    }
+
+    #[test]
+    fn test_update_replaces_unfinished_bar() {
+        let mut ema = EMA::new(EMAParams {
+            period: 3,
+            source: PriceSource::Close,
+        });
+        let kline = |close| Kline {
+            close,
+            ..Default::default()
+        };
+
+        ema.update(&kline(100.0), false);
+        ema.update(&kline(200.0), false);
+        ema.update(&kline(90.0), true);
+        let value = ema.update(&kline(110.0), true);
+
+        assert_eq!(value, vec![105.0]);
+        assert_eq!(ema.update(&kline(105.0), false), vec![105.0]);
+    }
 }
@@ -0,0 +1,35 @@
+use std::{
+    fmt::{self, Display},
+    hash::{Hash, Hasher},
+};
+
+// A multiplier in indicator params, e.g. the band width of Bollinger Bands.
+// Compared and hashed by its bits so params stay usable as calculator keys.
+#[derive(Debug, Clone, Copy)]
+pub struct Factor(pub f64);
+
+impl Factor {
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl PartialEq for Factor {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for Factor {}
+
+impl Hash for Factor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl Display for Factor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
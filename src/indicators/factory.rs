@@ -1,4 +1,10 @@
-use super::{ema::EMA, rsi::RSI, Indicator, IndicatorIdentifier};
+use super::{
+    adx::ADX, aroon::Aroon, atr::ATR, bollinger::Bollinger, cci::CCI, cmf::CMF, dema::DEMA, donchian::Donchian,
+    ema::EMA, hma::HMA, ichimoku::Ichimoku, kama::KAMA, keltner::Keltner, macd::MACD, mfi::MFI, nested::Nested,
+    obv::OBV, patterns::Patterns, rsi::RSI, sma::SMA, stoch_rsi::StochRSI, stochastic::Stochastic,
+    supertrend::SuperTrend, tema::TEMA, tr::TR, volume_profile::VolumeProfile, vwap::VWAP, williams_r::WilliamsR,
+    wma::WMA, Indicator, IndicatorIdentifier,
+};
 
 pub struct Factory {}
 
@@ -7,6 +13,57 @@ impl Factory {
         match indicator {
             IndicatorIdentifier::RSI(params) => Box::new(RSI::new(params.clone())),
             IndicatorIdentifier::EMA(params) => Box::new(EMA::new(params.clone())),
+            IndicatorIdentifier::SMA(params) => Box::new(SMA::new(params.clone())),
+            IndicatorIdentifier::WMA(params) => Box::new(WMA::new(params.clone())),
+            IndicatorIdentifier::DEMA(params) => Box::new(DEMA::new(params.clone())),
+            IndicatorIdentifier::TEMA(params) => Box::new(TEMA::new(params.clone())),
+            IndicatorIdentifier::HMA(params) => Box::new(HMA::new(params.clone())),
+            IndicatorIdentifier::KAMA(params) => Box::new(KAMA::new(params.clone())),
+            IndicatorIdentifier::MACD(params) => Box::new(MACD::new(params.clone())),
+            IndicatorIdentifier::TR => Box::new(TR::new()),
+            IndicatorIdentifier::ATR(params) => Box::new(ATR::new(params.clone())),
+            IndicatorIdentifier::Bollinger(params) => Box::new(Bollinger::new(params.clone())),
+            IndicatorIdentifier::Keltner(params) => Box::new(Keltner::new(params.clone())),
+            IndicatorIdentifier::Donchian(params) => Box::new(Donchian::new(params.clone())),
+            IndicatorIdentifier::VWAP(params) => Box::new(VWAP::new(params.clone())),
+            IndicatorIdentifier::OBV => Box::new(OBV::new()),
+            IndicatorIdentifier::MFI(params) => Box::new(MFI::new(params.clone())),
+            IndicatorIdentifier::CMF(params) => Box::new(CMF::new(params.clone())),
+            IndicatorIdentifier::VolumeProfile(params) => Box::new(VolumeProfile::new(params.clone())),
+            IndicatorIdentifier::ADX(params) => Box::new(ADX::new(params.clone())),
+            IndicatorIdentifier::Stochastic(params) => Box::new(Stochastic::new(params.clone())),
+            IndicatorIdentifier::StochRSI(params) => Box::new(StochRSI::new(params.clone())),
+            IndicatorIdentifier::CCI(params) => Box::new(CCI::new(params.clone())),
+            IndicatorIdentifier::WilliamsR(params) => Box::new(WilliamsR::new(params.clone())),
+            IndicatorIdentifier::Aroon(params) => Box::new(Aroon::new(params.clone())),
+            IndicatorIdentifier::SuperTrend(params) => Box::new(SuperTrend::new(params.clone())),
+            IndicatorIdentifier::Ichimoku(params) => Box::new(Ichimoku::new(params.clone())),
+            IndicatorIdentifier::Patterns(params) => Box::new(Patterns::new(params.clone())),
+            IndicatorIdentifier::Nested(params) => Box::new(Nested::new(params.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::catch_unwind;
+
+    use crate::indicators::{
+        dema::DEMAParams, hma::HMAParams, price::PriceSource, tema::TEMAParams, IndicatorIdentifier,
+    };
+
+    use super::Factory;
+
+    #[test]
+    fn test_zero_periods_are_rejected() {
+        let (period, source) = (0, PriceSource::Close);
+
+        for indicator in [
+            IndicatorIdentifier::DEMA(DEMAParams { period, source }),
+            IndicatorIdentifier::TEMA(TEMAParams { period, source }),
+            IndicatorIdentifier::HMA(HMAParams { period, source }),
+        ] {
+            assert!(catch_unwind(|| Factory::create(&indicator)).is_err());
         }
     }
 }
@@ -0,0 +1,108 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{price::PriceSource, running::RunningWma, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct HMAParams {
+    pub period: usize,
+    pub source: PriceSource,
+}
+
+impl HMAParams {
+    pub fn name(&self) -> String {
+        format!("hma_{}{}", self.period, self.source.suffix())
+    }
+}
+
+// Hull moving average: WMA(2 * WMA(n / 2) - WMA(n)) over sqrt(n) bars.
+#[allow(clippy::upper_case_acronyms)]
+pub struct HMA {
+    pub params: HMAParams,
+    half: RunningWma,
+    full: RunningWma,
+    hull: RunningWma,
+}
+
+impl HMA {
+    pub fn new(params: HMAParams) -> Self {
+        assert!(params.period > 0, "HMA period has to be positive");
+
+        HMA {
+            half: RunningWma::new(params.period / 2),
+            full: RunningWma::new(params.period),
+            hull: RunningWma::new(Self::root(params.period)),
+            params,
+        }
+    }
+
+    fn root(period: usize) -> usize {
+        ((period as f64).sqrt() as usize).max(1)
+    }
+}
+
+impl Indicator for HMA {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(HMA::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period + Self::root(self.params.period) - 1
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for HMA {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let price = self.params.source.value(kline);
+        let half = self.half.update(price, replace);
+        let full = self.full.update(price, replace);
+
+        vec![self.hull.update(2.0 * half - full, replace)]
+    }
+
+    fn reset(&mut self) {
+        self.half.reset();
+        self.full.reset();
+        self.hull.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices},
+        indicators::{
+            helpers::{assert_reference, PRICES},
+            price::PriceSource,
+            Indicator,
+        },
+    };
+
+    use super::{HMAParams, HMA};
+
+    #[test]
+    fn test_hma_reference_values() {
+        let history = History::with_klines(generate_klines_with_prices(&PRICES));
+        let hma = HMA::new(HMAParams {
+            period: 9,
+            source: PriceSource::Close,
+        });
+
+        let values = hma.calculate(&history);
+
+        assert_reference(
+            &values,
+            hma.warmup(),
+            &[
+                46.2907, 46.3395, 46.1566, 46.1036, 46.1609, 46.1946, 46.1796, 46.2223, 46.2613, 46.1176,
+            ],
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{
+    donchian::{Donchian, DonchianParams},
+    series, IncrementalIndicator, Indicator,
+};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct IchimokuParams {
+    pub conversion: usize,
+    pub base: usize,
+    pub span_b: usize,
+    pub displacement: usize,
+}
+
+impl Default for IchimokuParams {
+    fn default() -> Self {
+        IchimokuParams {
+            conversion: 9,
+            base: 26,
+            span_b: 52,
+            displacement: 26,
+        }
+    }
+}
+
+impl IchimokuParams {
+    pub fn name(&self) -> String {
+        format!(
+            "ichimoku_{}_{}_{}_{}",
+            self.conversion, self.base, self.span_b, self.displacement
+        )
+    }
+}
+
+// Ichimoku Kinko Hyo. The values are those calculated at each bar; the
+// leading spans are plotted `displacement` bars ahead of it and the lagging
+// span, the close, as many bars behind. Read them as plotted through
+// `History::get_displaced_output`.
+pub struct Ichimoku {
+    pub params: IchimokuParams,
+    conversion: Donchian,
+    base: Donchian,
+    span_b: Donchian,
+}
+
+impl Ichimoku {
+    pub const OUTPUTS: [&'static str; 5] = ["conversion", "base", "span_a", "span_b", "lagging"];
+
+    pub fn new(params: IchimokuParams) -> Self {
+        let channel = |period| Donchian::new(DonchianParams { period });
+
+        Ichimoku {
+            conversion: channel(params.conversion),
+            base: channel(params.base),
+            span_b: channel(params.span_b),
+            params,
+        }
+    }
+}
+
+impl Indicator for Ichimoku {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(Ichimoku::new(self.params.clone()), history)
+    }
+
+    // until every line is calculated; the leading spans are plotted on the
+    // current bar `displacement` bars later
+    fn warmup(&self) -> usize {
+        self.params.conversion.max(self.params.base).max(self.params.span_b)
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn displacement(&self) -> Vec<isize> {
+        let displacement = self.params.displacement as isize;
+        vec![0, 0, displacement, displacement, -displacement]
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for Ichimoku {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        // the middle of the channels
+        let conversion = self.conversion.update(kline, replace)[2];
+        let base = self.base.update(kline, replace)[2];
+        let span_b = self.span_b.update(kline, replace)[2];
+
+        vec![conversion, base, (conversion + base) / 2.0, span_b, kline.close]
+    }
+
+    fn reset(&mut self) {
+        self.conversion.reset();
+        self.base.reset();
+        self.span_b.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            factory::Factory,
+            helpers::{assert_output_reference, bars},
+            IndicatorIdentifier,
+        },
+    };
+
+    use super::IchimokuParams;
+
+    fn ichimoku() -> IndicatorIdentifier {
+        IndicatorIdentifier::Ichimoku(IchimokuParams {
+            conversion: 3,
+            base: 5,
+            span_b: 10,
+            displacement: 5,
+        })
+    }
+
+    fn history(count: usize) -> History {
+        let mut history = History::new();
+        history.request_calculators(&[ichimoku()]);
+
+        for kline in bars().into_iter().take(count) {
+            history.insert(kline);
+        }
+
+        history
+    }
+
+    #[test]
+    fn test_ichimoku_reference_values() {
+        let history = history(20);
+
+        assert_eq!(Factory::create(&ichimoku()).warmup(), 10);
+        assert_output_reference(
+            &history,
+            &ichimoku(),
+            "conversion",
+            &[
+                45.64, 45.8, 46.01, 45.895, 45.995, 45.995, 45.995, 46.19, 46.255, 46.255, 46.075,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &ichimoku(),
+            "base",
+            &[
+                45.255, 45.505, 45.64, 45.8, 45.995, 45.995, 45.995, 45.995, 46.06, 46.255, 46.075,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &ichimoku(),
+            "span_a",
+            &[
+                45.4475, 45.6525, 45.825, 45.8475, 45.995, 45.995, 45.995, 46.0925, 46.1575, 46.255, 46.075,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &ichimoku(),
+            "span_b",
+            &[
+                44.895, 44.895, 44.895, 44.895, 44.995, 45.355, 45.605, 45.74, 45.965, 46.06, 46.06,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_spans_are_displaced() {
+        let history = history(20);
+        let span_a = history.get_indicator_output(&ichimoku(), "span_a", 10);
+        let closes = history.window(20).close;
+
+        // the spans calculated five bars before the last five
+        assert_eq!(history.get_displaced_output(&ichimoku(), "span_a", 5), span_a[..5]);
+        assert_eq!(history.get_projected_output(&ichimoku(), "span_a"), span_a[5..]);
+        // the close plotted five bars back; the last five bars have none yet
+        assert_eq!(history.get_displaced_output(&ichimoku(), "lagging", 10), closes[15..]);
+        assert!(history.get_projected_output(&ichimoku(), "lagging").is_empty());
+        assert!(history.get_displaced_output(&ichimoku(), "cloud", 5).is_empty());
+    }
+
+    #[test]
+    fn test_cloud_has_no_lookahead() {
+        let full = history(20);
+
+        for count in 15..20 {
+            let partial = history(count);
+
+            for output in ["span_a", "span_b"] {
+                let cloud = partial.get_displaced_output(&ichimoku(), output, 1);
+                let later = full.get_displaced_output(&ichimoku(), output, 20 - count + 1);
+
+                assert_eq!(cloud[0], later[0]);
+            }
+        }
+    }
+}
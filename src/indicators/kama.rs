@@ -0,0 +1,217 @@
+use std::collections::VecDeque;
+
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{price::PriceSource, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct KAMAParams {
+    pub period: usize,
+    pub fast: usize,
+    pub slow: usize,
+    pub source: PriceSource,
+}
+
+impl KAMAParams {
+    pub fn name(&self) -> String {
+        format!(
+            "kama_{}_{}_{}{}",
+            self.period,
+            self.fast,
+            self.slow,
+            self.source.suffix()
+        )
+    }
+}
+
+// Kaufman's adaptive moving average. The smoothing moves between the fast and
+// slow EMA constants with the efficiency ratio, the net change over `period`
+// bars divided by the sum of the bar to bar changes. Until `period` changes
+// are known it follows the price.
+#[allow(clippy::upper_case_acronyms)]
+pub struct KAMA {
+    pub params: KAMAParams,
+    prices: VecDeque<f64>,
+    changes: VecDeque<f64>,
+    volatility: f64,
+    // value as of the bar before the last one, kept to replace the last bar
+    previous: Option<f64>,
+    current: Option<f64>,
+}
+
+impl KAMA {
+    pub fn new(params: KAMAParams) -> Self {
+        KAMA {
+            params,
+            prices: VecDeque::new(),
+            changes: VecDeque::new(),
+            volatility: 0.0,
+            previous: None,
+            current: None,
+        }
+    }
+
+    fn smoothing(period: usize) -> f64 {
+        2.0 / (period as f64 + 1.0)
+    }
+
+    fn push(&mut self, price: f64) {
+        if let Some(last) = self.prices.back() {
+            let change = (price - last).abs();
+            self.changes.push_back(change);
+            self.volatility += change;
+
+            if self.changes.len() > self.params.period {
+                self.volatility -= self.changes.pop_front().unwrap_or_default();
+            }
+        }
+
+        self.prices.push_back(price);
+        if self.prices.len() > self.params.period + 1 {
+            self.prices.pop_front();
+        }
+    }
+
+    fn replace(&mut self, price: f64) {
+        let count = self.prices.len();
+
+        if count >= 2 {
+            let change = (price - self.prices[count - 2]).abs();
+            if let Some(last) = self.changes.back_mut() {
+                self.volatility += change - *last;
+                *last = change;
+            }
+        }
+
+        if let Some(last) = self.prices.back_mut() {
+            *last = price;
+        }
+    }
+}
+
+impl Indicator for KAMA {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(KAMA::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period + 1
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for KAMA {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let price = self.params.source.value(kline);
+
+        match replace && !self.prices.is_empty() {
+            true => self.replace(price),
+            false => {
+                self.previous = self.current;
+                self.push(price);
+            }
+        }
+
+        let kama = match (self.previous, self.changes.len() == self.params.period) {
+            (Some(previous), true) => {
+                let net = (price - self.prices[0]).abs();
+                let ratio = if self.volatility > 0.0 {
+                    net / self.volatility
+                } else {
+                    0.0
+                };
+                let fast = Self::smoothing(self.params.fast);
+                let slow = Self::smoothing(self.params.slow);
+                let constant = (ratio * (fast - slow) + slow).powi(2);
+
+                previous + constant * (price - previous)
+            }
+            _ => price,
+        };
+        self.current = Some(kama);
+
+        vec![kama]
+    }
+
+    fn reset(&mut self) {
+        self.prices.clear();
+        self.changes.clear();
+        self.volatility = 0.0;
+        self.previous = None;
+        self.current = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{
+            history::History,
+            kline::{helpers::generate_klines_with_prices, Kline},
+        },
+        indicators::{
+            helpers::{assert_reference, PRICES},
+            price::PriceSource,
+            IncrementalIndicator, Indicator,
+        },
+    };
+
+    use super::{KAMAParams, KAMA};
+
+    #[test]
+    fn test_kama_reference_values() {
+        let history = History::with_klines(generate_klines_with_prices(&PRICES));
+        let kama = KAMA::new(KAMAParams {
+            period: 10,
+            fast: 2,
+            slow: 30,
+            source: PriceSource::Close,
+        });
+
+        let values = kama.calculate(&history);
+
+        assert_reference(
+            &values,
+            kama.warmup(),
+            &[
+                46.0593, 46.0544, 46.0148, 46.0754, 46.1141, 46.1036, 46.0982, 46.1226, 46.1249, 46.113,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_kama_replaces_unfinished_bar() {
+        let klines = generate_klines_with_prices(&PRICES);
+        let params = KAMAParams {
+            period: 10,
+            fast: 2,
+            slow: 30,
+            source: PriceSource::Close,
+        };
+        let expected = KAMA::new(params.clone()).calculate(&History::with_klines(klines.clone()));
+        let mut kama = KAMA::new(params);
+
+        let values = klines
+            .iter()
+            .flat_map(|kline| {
+                let partial = Kline {
+                    close: kline.close + 1.0,
+                    ..kline.clone()
+                };
+                kama.update(&partial, false);
+                kama.update(kline, true)
+            })
+            .collect::<Vec<f64>>();
+
+        for (value, expected) in values.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{
+    atr::{ATRParams, ATR},
+    factor::Factor,
+    price::PriceSource,
+    running::RunningEma,
+    series, IncrementalIndicator, Indicator,
+};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct KeltnerParams {
+    pub period: usize,
+    pub atr_period: usize,
+    // width of the channel in average true ranges
+    pub multiplier: Factor,
+    pub source: PriceSource,
+}
+
+impl KeltnerParams {
+    pub fn name(&self) -> String {
+        format!(
+            "kc_{}_{}_{}{}",
+            self.period,
+            self.atr_period,
+            self.multiplier,
+            self.source.suffix()
+        )
+    }
+}
+
+// A channel a multiple of the average true range around an EMA.
+pub struct Keltner {
+    pub params: KeltnerParams,
+    mid: RunningEma,
+    atr: ATR,
+}
+
+impl Keltner {
+    pub const OUTPUTS: [&'static str; 3] = ["mid", "upper", "lower"];
+
+    pub fn new(params: KeltnerParams) -> Self {
+        Keltner {
+            mid: RunningEma::new(params.period),
+            atr: ATR::new(ATRParams {
+                period: params.atr_period,
+            }),
+            params,
+        }
+    }
+}
+
+impl Indicator for Keltner {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(Keltner::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period.max(self.params.atr_period)
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for Keltner {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let mid = self.mid.update(self.params.source.value(kline), replace);
+        let width = self.params.multiplier.value() * self.atr.value(kline, replace);
+
+        vec![mid, mid + width, mid - width]
+    }
+
+    fn reset(&mut self) {
+        self.mid.reset();
+        IncrementalIndicator::reset(&mut self.atr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            factor::Factor,
+            factory::Factory,
+            helpers::{assert_output_reference, bars},
+            price::PriceSource,
+            IndicatorIdentifier,
+        },
+    };
+
+    use super::KeltnerParams;
+
+    #[test]
+    fn test_keltner_reference_values() {
+        let keltner = IndicatorIdentifier::Keltner(KeltnerParams {
+            period: 5,
+            atr_period: 5,
+            multiplier: Factor(1.5),
+            source: PriceSource::Close,
+        });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&keltner));
+
+        for kline in bars() {
+            history.insert(kline);
+        }
+
+        assert_eq!(Factory::create(&keltner).warmup(), 5);
+        assert_output_reference(
+            &history,
+            &keltner,
+            "mid",
+            &[
+                44.1216, 44.3577, 44.6052, 44.8768, 45.1978, 45.4919, 45.6246, 45.7597, 45.7098, 45.8999, 46.0266,
+                46.0177, 46.0218, 46.1512, 46.1741, 45.9961,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &keltner,
+            "upper",
+            &[
+                45.3426, 45.6345, 45.8576, 46.1247, 46.4722, 46.7334, 46.8248, 46.9119, 46.9075, 47.2091, 47.2239,
+                47.2096, 47.1343, 47.3052, 47.3043, 47.2243,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &keltner,
+            "lower",
+            &[
+                42.9006, 43.0809, 43.3527, 43.6288, 43.9235, 44.2504, 44.4244, 44.6076, 44.5121, 44.5907, 44.8292,
+                44.8259, 44.9093, 44.9972, 45.0439, 44.7679,
+            ],
+        );
+    }
+}
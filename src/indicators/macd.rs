@@ -0,0 +1,173 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{price::PriceSource, running::RunningEma, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct MACDParams {
+    pub fast: usize,
+    pub slow: usize,
+    pub signal: usize,
+    pub source: PriceSource,
+}
+
+impl MACDParams {
+    pub fn name(&self) -> String {
+        format!(
+            "macd_{}_{}_{}{}",
+            self.fast,
+            self.slow,
+            self.signal,
+            self.source.suffix()
+        )
+    }
+}
+
+// The difference between a fast and a slow EMA, its signal line and the
+// histogram between both.
+#[allow(clippy::upper_case_acronyms)]
+pub struct MACD {
+    pub params: MACDParams,
+    fast: RunningEma,
+    slow: RunningEma,
+    signal: RunningEma,
+}
+
+impl MACD {
+    pub const OUTPUTS: [&'static str; 3] = ["macd", "signal", "histogram"];
+
+    pub fn new(params: MACDParams) -> Self {
+        assert!(params.fast > 0 && params.signal > 0, "MACD periods have to be positive");
+        assert!(
+            params.fast < params.slow,
+            "MACD fast period has to be shorter than the slow one"
+        );
+
+        MACD {
+            fast: RunningEma::new(params.fast),
+            slow: RunningEma::new(params.slow),
+            signal: RunningEma::new(params.signal),
+            params,
+        }
+    }
+}
+
+impl Indicator for MACD {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(MACD::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.fast.max(self.params.slow) + self.params.signal - 1
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for MACD {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let price = self.params.source.value(kline);
+        let macd = self.fast.update(price, replace) - self.slow.update(price, replace);
+        let signal = self.signal.update(macd, replace);
+
+        vec![macd, signal, macd - signal]
+    }
+
+    fn reset(&mut self) {
+        self.fast.reset();
+        self.slow.reset();
+        self.signal.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::catch_unwind;
+
+    use crate::{
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices},
+        indicators::{
+            factory::Factory,
+            helpers::{assert_output_reference, PRICES},
+            price::PriceSource,
+            IndicatorIdentifier,
+        },
+    };
+
+    use super::{MACDParams, MACD};
+
+    fn macd() -> IndicatorIdentifier {
+        IndicatorIdentifier::MACD(MACDParams {
+            fast: 5,
+            slow: 10,
+            signal: 4,
+            source: PriceSource::Close,
+        })
+    }
+
+    #[test]
+    fn test_macd_reference_values() {
+        let mut history = History::new();
+        history.request_calculators(&[macd()]);
+
+        for kline in generate_klines_with_prices(&PRICES) {
+            history.insert(kline);
+        }
+
+        assert_eq!(Factory::create(&macd()).warmup(), 13);
+        assert_output_reference(
+            &history,
+            &macd(),
+            "macd",
+            &[0.2999, 0.3317, 0.329, 0.2652, 0.2188, 0.2378, 0.205, 0.0868],
+        );
+        assert_output_reference(
+            &history,
+            &macd(),
+            "signal",
+            &[0.3385, 0.3358, 0.3331, 0.3059, 0.2711, 0.2578, 0.2367, 0.1767],
+        );
+        assert_output_reference(
+            &history,
+            &macd(),
+            "histogram",
+            &[-0.0387, -0.0041, -0.0041, -0.0408, -0.0523, -0.0199, -0.0317, -0.0899],
+        );
+    }
+
+    #[test]
+    fn test_unknown_output() {
+        let mut history = History::new();
+        history.request_calculators(&[macd()]);
+
+        for kline in generate_klines_with_prices(&PRICES) {
+            history.insert(kline);
+        }
+
+        assert_eq!(history.get_indicator_values(&macd(), 1)[0].len(), 3);
+        assert!(history.get_indicator_output(&macd(), "value", 20).is_empty());
+    }
+
+    #[test]
+    fn test_rejects_invalid_periods() {
+        for (fast, slow, signal) in [(0, 10, 4), (5, 10, 0), (10, 10, 4), (10, 5, 4)] {
+            let params = MACDParams {
+                fast,
+                slow,
+                signal,
+                source: PriceSource::Close,
+            };
+
+            assert!(catch_unwind(|| MACD::new(params)).is_err());
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{price::PriceSource, running::RunningSma, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct MFIParams {
+    pub period: usize,
+}
+
+impl MFIParams {
+    pub fn name(&self) -> String {
+        format!("mfi_{}", self.period)
+    }
+}
+
+// The money flow index, an RSI of the typical price weighted by volume. The
+// flow of a bar counts as positive when its typical price rose and as
+// negative when it fell.
+#[allow(clippy::upper_case_acronyms)]
+pub struct MFI {
+    pub params: MFIParams,
+    // typical price of the bar before the last one, kept to replace the last
+    // bar
+    previous: Option<f64>,
+    last: Option<f64>,
+    positive: RunningSma,
+    negative: RunningSma,
+}
+
+impl MFI {
+    pub fn new(params: MFIParams) -> Self {
+        MFI {
+            previous: None,
+            last: None,
+            positive: RunningSma::new(params.period),
+            negative: RunningSma::new(params.period),
+            params,
+        }
+    }
+}
+
+impl Indicator for MFI {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(MFI::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period + 1
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for MFI {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        if !replace {
+            self.previous = self.last;
+        }
+
+        let typical = PriceSource::HLC3.value(kline);
+        self.last = Some(typical);
+
+        let Some(previous) = self.previous else {
+            return vec![50.0];
+        };
+
+        let flow = typical * kline.volume;
+        let (positive, negative) = match typical.total_cmp(&previous) {
+            std::cmp::Ordering::Greater => (flow, 0.0),
+            std::cmp::Ordering::Less => (0.0, flow),
+            std::cmp::Ordering::Equal => (0.0, 0.0),
+        };
+        // the ratio of the averages is the ratio of the sums
+        let positive = self.positive.update(positive, replace);
+        let negative = self.negative.update(negative, replace);
+
+        match negative > 0.0 {
+            true => vec![100.0 - 100.0 / (1.0 + positive / negative)],
+            false => vec![100.0],
+        }
+    }
+
+    fn reset(&mut self) {
+        self.previous = None;
+        self.last = None;
+        self.positive.reset();
+        self.negative.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            helpers::{assert_reference, bars},
+            Indicator,
+        },
+    };
+
+    use super::{MFIParams, MFI};
+
+    #[test]
+    fn test_mfi_reference_values() {
+        let history = History::with_klines(bars());
+        let mfi = MFI::new(MFIParams { period: 5 });
+
+        assert_reference(
+            &mfi.calculate(&history),
+            mfi.warmup(),
+            &[
+                40.1676, 58.6567, 78.695, 100.0, 100.0, 83.2074, 83.2728, 63.3587, 63.4203, 63.4722, 63.44, 45.1227,
+                65.0747, 55.4404, 25.6627,
+            ],
+        );
+    }
+}
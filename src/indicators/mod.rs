@@ -1,19 +1,222 @@
+pub mod adx;
+pub mod aroon;
+pub mod atr;
+pub mod bollinger;
+pub mod cci;
+pub mod cmf;
+pub mod dema;
+pub mod donchian;
 pub mod ema;
+pub mod factor;
 pub mod factory;
+pub mod hma;
+pub mod ichimoku;
+pub mod kama;
+pub mod keltner;
+pub mod macd;
+pub mod mfi;
+pub mod nested;
+pub mod obv;
+pub mod patterns;
+pub mod price;
 pub mod rsi;
+mod running;
+pub mod sma;
+pub mod stoch_rsi;
+pub mod stochastic;
+pub mod supertrend;
+pub mod tema;
+pub mod tr;
+pub mod volume_profile;
+pub mod vwap;
+pub mod williams_r;
+pub mod wma;
 
+use adx::ADXParams;
+use aroon::AroonParams;
+use atr::ATRParams;
+use bollinger::BollingerParams;
+use cci::CCIParams;
+use cmf::CMFParams;
+use dema::DEMAParams;
+use donchian::DonchianParams;
 use ema::EMAParams;
+use hma::HMAParams;
+use ichimoku::IchimokuParams;
+use kama::KAMAParams;
+use keltner::KeltnerParams;
+use macd::MACDParams;
+use mfi::MFIParams;
+use nested::{Input, NestedParams};
+use patterns::PatternParams;
 use rsi::RSIParams;
+use sma::SMAParams;
+use stoch_rsi::StochRSIParams;
+use stochastic::StochasticParams;
+use supertrend::SuperTrendParams;
+use tema::TEMAParams;
+use volume_profile::VolumeProfileParams;
+use vwap::VWAPParams;
+use williams_r::WilliamsRParams;
+use wma::WMAParams;
 
-use crate::data_structures::history::History;
+use crate::data_structures::{history::History, kline::Kline};
 
 #[derive(Eq, Hash, PartialEq, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum IndicatorIdentifier {
     RSI(RSIParams),
     EMA(EMAParams),
+    SMA(SMAParams),
+    WMA(WMAParams),
+    DEMA(DEMAParams),
+    TEMA(TEMAParams),
+    HMA(HMAParams),
+    KAMA(KAMAParams),
+    MACD(MACDParams),
+    TR,
+    ATR(ATRParams),
+    Bollinger(BollingerParams),
+    Keltner(KeltnerParams),
+    Donchian(DonchianParams),
+    VWAP(VWAPParams),
+    OBV,
+    MFI(MFIParams),
+    CMF(CMFParams),
+    VolumeProfile(VolumeProfileParams),
+    ADX(ADXParams),
+    Stochastic(StochasticParams),
+    StochRSI(StochRSIParams),
+    CCI(CCIParams),
+    WilliamsR(WilliamsRParams),
+    Aroon(AroonParams),
+    SuperTrend(SuperTrendParams),
+    Ichimoku(IchimokuParams),
+    Patterns(PatternParams),
+    Nested(NestedParams),
 }
 
 pub trait Indicator {
     fn name(&self) -> String;
     fn calculate(&self, history: &History) -> Vec<f64>;
+
+    // Number of bars needed before the values are meaningful.
+    fn warmup(&self) -> usize;
+
+    // Names of the values produced for every bar, in order, for indicators
+    // with more than one output.
+    fn outputs(&self) -> &'static [&'static str] {
+        &["value"]
+    }
+
+    // Bars each output is plotted ahead of the bar it was calculated at, or
+    // behind it when negative. Missing outputs are not displaced.
+    fn displacement(&self) -> Vec<isize> {
+        Vec::new()
+    }
+
+    // Indicators calculated on another input than the bars name it here;
+    // `History` then feeds them bars priced at the value of the input.
+    fn input(&self) -> Option<&Input> {
+        None
+    }
+
+    // The running state `History` feeds one bar at a time, so inserting a bar
+    // never recalculates the whole history.
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Readiness {
+    Ready,
+    WarmingUp { remaining: usize },
+    // the indicator was never requested from the history
+    Unavailable,
+}
+
+pub trait IncrementalIndicator {
+    // Folds `kline` into the state in constant time and returns the values at
+    // that bar. With `replace`, `kline` is a newer version of the last bar and
+    // takes the place of its previous contribution.
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64>;
+    fn reset(&mut self);
+}
+
+// Feeds every bar of `history` through a fresh incremental indicator.
+fn series(mut indicator: impl IncrementalIndicator, history: &History) -> Vec<f64> {
+    let window = history.window(history.len());
+
+    (0..window.len())
+        .flat_map(|index| indicator.update(&window.kline(index), false))
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod helpers {
+    use crate::data_structures::{
+        history::History,
+        kline::{helpers::generate_klines_with_prices, Kline},
+    };
+
+    use super::{factory::Factory, IndicatorIdentifier};
+
+    // Closes used for the reference values of the indicator tests. The
+    // references were computed separately from the textbook definitions,
+    // with EMAs seeded by the first close as pandas `ewm(span, adjust=False)`
+    // does. TA-Lib seeds its EMAs with an SMA, so its first values differ.
+    pub(crate) const PRICES: [f64; 20] = [
+        44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61, 46.28, 46.28, 46.00,
+        46.03, 46.41, 46.22, 45.64,
+    ];
+
+    // Bars closing at `PRICES`, each opening at the previous close with wicks
+    // above and below the body and a cycling volume.
+    pub(crate) fn bars() -> Vec<Kline> {
+        let mut open = PRICES[0];
+
+        generate_klines_with_prices(&PRICES)
+            .into_iter()
+            .enumerate()
+            .map(|(index, kline)| {
+                let kline = Kline {
+                    open,
+                    high: open.max(kline.close) + 0.3,
+                    low: open.min(kline.close) - 0.2,
+                    volume: 1000.0 + 100.0 * (index % 5) as f64,
+                    ..kline
+                };
+                open = kline.close;
+                kline
+            })
+            .collect()
+    }
+
+    // Compares the values from the end of the warm-up on with reference values
+    // rounded to four decimals.
+    pub(crate) fn assert_reference(values: &[f64], warmup: usize, expected: &[f64]) {
+        assert_eq!(values.len() - (warmup - 1), expected.len());
+        assert_close(&values[warmup - 1..], expected);
+    }
+
+    // Compares the values `history` keeps for one output of `indicator`, which
+    // start at the end of its warm-up, with reference values.
+    pub(crate) fn assert_output_reference(
+        history: &History,
+        indicator: &IndicatorIdentifier,
+        output: &str,
+        expected: &[f64],
+    ) {
+        let values = history.get_indicator_output(indicator, output, history.len());
+        let warmup = Factory::create(indicator).warmup();
+
+        assert_eq!(values.len(), (history.len() + 1).saturating_sub(warmup.max(1)));
+        assert_eq!(values.len(), expected.len());
+        assert_close(&values, expected);
+    }
+
+    fn assert_close(values: &[f64], expected: &[f64]) {
+        for (value, expected) in values.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-4, "{} != {}", value, expected);
+        }
+    }
 }
@@ -0,0 +1,175 @@
+use log::warn;
+
+use crate::data_structures::history::History;
+
+use super::{factory::Factory, IncrementalIndicator, Indicator, IndicatorIdentifier};
+
+// What a nested indicator is calculated on instead of the prices.
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub enum Input {
+    Volume,
+    // one output of another indicator, by name
+    Indicator(Box<IndicatorIdentifier>, &'static str),
+}
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct NestedParams {
+    pub indicator: Box<IndicatorIdentifier>,
+    pub input: Input,
+}
+
+impl IndicatorIdentifier {
+    // This indicator calculated on `input`, e.g. an EMA of the RSI.
+    pub fn on(self, input: Input) -> IndicatorIdentifier {
+        IndicatorIdentifier::Nested(NestedParams {
+            indicator: Box::new(self),
+            input,
+        })
+    }
+}
+
+// An indicator fed with the values of its input in place of the prices:
+// every bar it sees opens, closes and ranges at the input's value. `History`
+// calculates the input first, once for all the indicators nested on it.
+pub struct Nested {
+    pub params: NestedParams,
+    indicator: Box<dyn Indicator>,
+    name: String,
+    input_warmup: usize,
+}
+
+impl Nested {
+    pub fn new(params: NestedParams) -> Self {
+        let indicator = Factory::create(&params.indicator);
+
+        let (input, input_warmup) = match &params.input {
+            Input::Volume => ("volume".to_string(), 1),
+            Input::Indicator(input, output) => {
+                let input = Factory::create(input);
+                if !input.outputs().contains(output) {
+                    warn!("{} has no output {}", input.name(), output);
+                }
+
+                match input.outputs().len() {
+                    1 => (input.name(), input.warmup()),
+                    _ => (format!("{}_{}", input.name(), output), input.warmup()),
+                }
+            }
+        };
+
+        Nested {
+            name: format!("{}_of_{}", indicator.name(), input),
+            indicator,
+            input_warmup,
+            params,
+        }
+    }
+}
+
+impl Indicator for Nested {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    // Calculated on a copy of the bars, so the input is not taken from
+    // `history` even when it was requested there.
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        let identifier = IndicatorIdentifier::Nested(self.params.clone());
+        let mut scratch = History::with_klines(history.window(history.len()).klines());
+        scratch.request_calculators(std::slice::from_ref(&identifier));
+
+        scratch.get_indicator_values(&identifier, history.len()).concat()
+    }
+
+    fn warmup(&self) -> usize {
+        (self.input_warmup + self.indicator.warmup()).saturating_sub(1)
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        self.indicator.outputs()
+    }
+
+    fn displacement(&self) -> Vec<isize> {
+        self.indicator.displacement()
+    }
+
+    fn input(&self) -> Option<&Input> {
+        Some(&self.params.input)
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self.indicator.incremental()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices},
+        indicators::{
+            ema::{EMAParams, EMA},
+            factory::Factory,
+            helpers::{bars, PRICES},
+            price::PriceSource,
+            rsi::{RSIParams, RSI},
+            sma::SMAParams,
+            Indicator, IndicatorIdentifier,
+        },
+    };
+
+    use super::Input;
+
+    fn rsi() -> IndicatorIdentifier {
+        IndicatorIdentifier::RSI(RSIParams { period: 5 })
+    }
+
+    fn ema_of_rsi() -> IndicatorIdentifier {
+        IndicatorIdentifier::EMA(EMAParams {
+            period: 5,
+            source: PriceSource::Close,
+        })
+        .on(Input::Indicator(Box::new(rsi()), "value"))
+    }
+
+    #[test]
+    fn test_ema_of_rsi() {
+        let history = History::with_klines(generate_klines_with_prices(&PRICES));
+        let rsi = RSI::new(RSIParams { period: 5 }).calculate(&history);
+        let expected = EMA::new(EMAParams {
+            period: 5,
+            source: PriceSource::Close,
+        })
+        .calculate(&History::with_klines(generate_klines_with_prices(&rsi)));
+
+        let mut nested = History::new();
+        nested.request_calculators(&[ema_of_rsi()]);
+        for kline in generate_klines_with_prices(&PRICES) {
+            nested.insert(kline);
+        }
+        let values = nested.get_indicator_values(&ema_of_rsi(), 20).concat();
+
+        assert_eq!(values.len(), 11);
+        assert_eq!(values.as_slice(), &expected[4..]);
+        assert_eq!(values, Factory::create(&ema_of_rsi()).calculate(&history));
+    }
+
+    #[test]
+    fn test_sma_of_volume() {
+        let sma = IndicatorIdentifier::SMA(SMAParams {
+            period: 3,
+            source: PriceSource::Close,
+        })
+        .on(Input::Volume);
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&sma));
+
+        for kline in bars() {
+            history.insert(kline);
+        }
+
+        assert_eq!(
+            history.get_indicator_values(&sma, 3).concat(),
+            vec![1100.0, 1200.0, 1300.0]
+        );
+    }
+}
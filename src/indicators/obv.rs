@@ -0,0 +1,88 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{series, IncrementalIndicator, Indicator};
+
+// On-balance volume: the running total of the volume of bars closing higher
+// minus the volume of bars closing lower. It starts at zero.
+#[derive(Default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct OBV {
+    // close and total as of the bar before the last one, kept to replace the
+    // last bar
+    previous: Option<(f64, f64)>,
+    current: Option<(f64, f64)>,
+}
+
+impl OBV {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Indicator for OBV {
+    fn name(&self) -> String {
+        "obv".to_string()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(OBV::new(), history)
+    }
+
+    fn warmup(&self) -> usize {
+        1
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for OBV {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        if !replace {
+            self.previous = self.current;
+        }
+
+        let total = match self.previous {
+            Some((close, total)) if kline.close > close => total + kline.volume,
+            Some((close, total)) if kline.close < close => total - kline.volume,
+            Some((_, total)) => total,
+            None => 0.0,
+        };
+        self.current = Some((kline.close, total));
+
+        vec![total]
+    }
+
+    fn reset(&mut self) {
+        self.previous = None;
+        self.current = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            helpers::{assert_reference, bars},
+            Indicator,
+        },
+    };
+
+    use super::OBV;
+
+    #[test]
+    fn test_obv_reference_values() {
+        let history = History::with_klines(bars());
+
+        assert_reference(
+            &OBV::new().calculate(&history),
+            1,
+            &[
+                0.0, -1100.0, 100.0, -1200.0, 200.0, 1200.0, 2300.0, 3500.0, 4800.0, 6200.0, 5200.0, 6300.0, 5100.0,
+                6400.0, 6400.0, 5400.0, 6500.0, 7700.0, 6400.0, 5000.0,
+            ],
+        );
+    }
+}
@@ -0,0 +1,381 @@
+use std::collections::VecDeque;
+
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{factor::Factor, running::RunningWindow, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct PatternParams {
+    // largest body of a doji relative to its range
+    pub doji_body: Factor,
+    // smallest lower wick of a hammer relative to its body
+    pub hammer_wick: Factor,
+    // largest body of the middle bar of a star relative to its range; the
+    // bars around it and the soldiers and crows need a larger one
+    pub small_body: Factor,
+    // bars the trend before a hammer or hanging man is measured over
+    pub trend: usize,
+}
+
+impl Default for PatternParams {
+    fn default() -> Self {
+        PatternParams {
+            doji_body: Factor(0.1),
+            hammer_wick: Factor(2.0),
+            small_body: Factor(0.3),
+            trend: 5,
+        }
+    }
+}
+
+impl PatternParams {
+    pub fn name(&self) -> String {
+        format!(
+            "patterns_{}_{}_{}_{}",
+            self.doji_body, self.hammer_wick, self.small_body, self.trend
+        )
+    }
+}
+
+// The shape of a bar.
+struct Candle {
+    open: f64,
+    close: f64,
+    body: f64,
+    range: f64,
+    upper: f64,
+    lower: f64,
+}
+
+impl Candle {
+    fn new(kline: &Kline) -> Self {
+        Candle {
+            open: kline.open,
+            close: kline.close,
+            body: (kline.close - kline.open).abs(),
+            range: kline.high - kline.low,
+            upper: kline.high - kline.open.max(kline.close),
+            lower: kline.open.min(kline.close) - kline.low,
+        }
+    }
+
+    fn rising(&self) -> bool {
+        self.close > self.open
+    }
+
+    fn falling(&self) -> bool {
+        self.close < self.open
+    }
+
+    fn small(&self, share: f64) -> bool {
+        self.body <= share * self.range
+    }
+
+    fn middle(&self) -> f64 {
+        (self.open + self.close) / 2.0
+    }
+}
+
+// Candlestick patterns completed by each bar, as one output per pattern: 1
+// for the bullish form, -1 for the bearish one and 0 when there is none.
+// Hammer and hanging man share a shape and are told apart by the trend
+// leading into them.
+pub struct Patterns {
+    pub params: PatternParams,
+    klines: VecDeque<Kline>,
+    closes: RunningWindow,
+}
+
+impl Patterns {
+    pub const OUTPUTS: [&'static str; 7] = ["engulfing", "hammer", "doji", "star", "soldiers", "inside", "outside"];
+
+    pub fn new(params: PatternParams) -> Self {
+        Patterns {
+            klines: VecDeque::new(),
+            closes: RunningWindow::new(params.trend + 2),
+            params,
+        }
+    }
+
+    fn engulfing(previous: &Candle, current: &Candle) -> f64 {
+        if current.body <= previous.body {
+            return 0.0;
+        }
+
+        if previous.falling() && current.rising() && current.open <= previous.close && current.close >= previous.open {
+            1.0
+        } else if previous.rising()
+            && current.falling()
+            && current.open >= previous.close
+            && current.close <= previous.open
+        {
+            -1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn hammer(&self, current: &Candle) -> f64 {
+        let shape = current.lower > 0.0
+            && current.lower >= self.params.hammer_wick.value() * current.body
+            && current.upper <= current.body;
+        let closes = self.closes.values();
+
+        // the close before the bar against the one `trend` bars earlier
+        if !shape || closes.len() < self.params.trend + 2 {
+            return 0.0;
+        }
+        match closes[closes.len() - 2].total_cmp(&closes[0]) {
+            std::cmp::Ordering::Less => 1.0,
+            std::cmp::Ordering::Greater => -1.0,
+            std::cmp::Ordering::Equal => 0.0,
+        }
+    }
+
+    fn star(&self, first: &Candle, middle: &Candle, last: &Candle) -> f64 {
+        let small = self.params.small_body.value();
+        let long = |candle: &Candle| !candle.small(small);
+
+        if !long(first) || !middle.small(small) || !long(last) {
+            return 0.0;
+        }
+        let below = middle.open.max(middle.close) <= first.close;
+        let above = middle.open.min(middle.close) >= first.close;
+
+        if first.falling() && below && last.rising() && last.close > first.middle() {
+            1.0
+        } else if first.rising() && above && last.falling() && last.close < first.middle() {
+            -1.0
+        } else {
+            0.0
+        }
+    }
+
+    // Three long bars in one direction, each opening within the body of the
+    // one before and closing beyond it.
+    fn soldiers(&self, candles: [&Candle; 3]) -> f64 {
+        let long = candles
+            .iter()
+            .all(|candle| !candle.small(self.params.small_body.value()));
+        let within = |previous: &Candle, current: &Candle| {
+            current.open >= previous.open.min(previous.close) && current.open <= previous.open.max(previous.close)
+        };
+        let chained = within(candles[0], candles[1]) && within(candles[1], candles[2]);
+
+        if !long || !chained {
+            return 0.0;
+        }
+        if candles.iter().all(|candle| candle.rising())
+            && candles[1].close > candles[0].close
+            && candles[2].close > candles[1].close
+        {
+            return 1.0;
+        }
+        if candles.iter().all(|candle| candle.falling())
+            && candles[1].close < candles[0].close
+            && candles[2].close < candles[1].close
+        {
+            return -1.0;
+        }
+
+        0.0
+    }
+}
+
+impl Indicator for Patterns {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(Patterns::new(self.params.clone()), history)
+    }
+
+    // patterns spanning bars that did not arrive yet are simply absent
+    fn warmup(&self) -> usize {
+        1
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for Patterns {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        if replace {
+            self.klines.pop_back();
+        }
+        self.klines.push_back(kline.clone());
+        if self.klines.len() > 3 {
+            self.klines.pop_front();
+        }
+        self.closes.update(kline.close, replace);
+
+        let candles = self.klines.iter().map(Candle::new).collect::<Vec<Candle>>();
+        let current = &candles[candles.len() - 1];
+        let flag = |pattern: bool| if pattern { 1.0 } else { 0.0 };
+        let doji = flag(current.range > 0.0 && current.small(self.params.doji_body.value()));
+
+        let (engulfing, inside, outside) = match self.klines.len() {
+            1 => (0.0, 0.0, 0.0),
+            count => {
+                let (previous, last) = (&self.klines[count - 2], &self.klines[count - 1]);
+
+                (
+                    Self::engulfing(&candles[count - 2], current),
+                    flag(last.high < previous.high && last.low > previous.low),
+                    flag(last.high > previous.high && last.low < previous.low),
+                )
+            }
+        };
+        let (star, soldiers) = match candles.as_slice() {
+            [first, middle, last] => (self.star(first, middle, last), self.soldiers([first, middle, last])),
+            _ => (0.0, 0.0),
+        };
+
+        vec![engulfing, self.hammer(current), doji, star, soldiers, inside, outside]
+    }
+
+    fn reset(&mut self) {
+        self.klines.clear();
+        self.closes.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{
+            history::History,
+            kline::{helpers::generate_klines_with_prices, Kline},
+        },
+        indicators::{factor::Factor, IndicatorIdentifier},
+    };
+
+    use super::PatternParams;
+
+    // Bars from (open, high, low, close).
+    fn candles(bars: &[(f64, f64, f64, f64)]) -> Vec<Kline> {
+        let closes = bars.iter().map(|bar| bar.3).collect::<Vec<f64>>();
+
+        generate_klines_with_prices(&closes)
+            .into_iter()
+            .zip(bars)
+            .map(|(kline, (open, high, low, _))| Kline {
+                open: *open,
+                high: *high,
+                low: *low,
+                ..kline
+            })
+            .collect()
+    }
+
+    // The flags of `pattern` for every bar.
+    fn flags(params: PatternParams, bars: &[(f64, f64, f64, f64)], pattern: &str) -> Vec<f64> {
+        let patterns = IndicatorIdentifier::Patterns(params);
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&patterns));
+
+        for kline in candles(bars) {
+            history.insert(kline);
+        }
+
+        history.get_indicator_output(&patterns, pattern, bars.len())
+    }
+
+    fn short_trend() -> PatternParams {
+        PatternParams {
+            trend: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_engulfing() {
+        let bars = [
+            (10.0, 10.2, 9.4, 9.5),
+            (9.4, 10.6, 9.3, 10.5),
+            (10.3, 10.7, 10.2, 10.6),
+            (10.7, 10.8, 9.8, 9.9),
+        ];
+
+        assert_eq!(flags(short_trend(), &bars, "engulfing"), vec![0.0, 1.0, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn test_hammer_and_hanging_man() {
+        let bars = [
+            (12.0, 12.1, 11.4, 11.5),
+            (11.5, 11.6, 10.9, 11.0),
+            (10.9, 11.0, 9.5, 10.8),
+            (11.0, 12.1, 10.9, 12.0),
+            (12.9, 13.0, 11.6, 13.0),
+        ];
+
+        assert_eq!(flags(short_trend(), &bars, "hammer"), vec![0.0, 0.0, 1.0, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn test_doji_threshold() {
+        let bars = [
+            (10.0, 10.5, 9.5, 10.05),
+            (10.0, 10.5, 9.5, 10.15),
+            (10.0, 10.5, 9.5, 10.4),
+        ];
+        let wide = PatternParams {
+            doji_body: Factor(0.2),
+            ..short_trend()
+        };
+
+        assert_eq!(flags(short_trend(), &bars, "doji"), vec![1.0, 0.0, 0.0]);
+        assert_eq!(flags(wide, &bars, "doji"), vec![1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_morning_and_evening_star() {
+        let bars = [
+            (11.0, 11.1, 9.9, 10.0),
+            (9.8, 9.9, 9.6, 9.85),
+            (10.0, 10.9, 9.9, 10.8),
+            (10.9, 12.1, 10.8, 12.0),
+            (12.2, 12.4, 12.1, 12.15),
+            (12.0, 12.1, 10.9, 11.0),
+        ];
+
+        assert_eq!(flags(short_trend(), &bars, "star"), vec![0.0, 0.0, 1.0, 0.0, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn test_three_soldiers_and_crows() {
+        let bars = [
+            (10.0, 10.6, 9.9, 10.5),
+            (10.3, 11.1, 10.2, 11.0),
+            (10.8, 11.6, 10.7, 11.5),
+            (11.5, 11.6, 10.9, 11.0),
+            (11.2, 11.3, 10.4, 10.5),
+            (10.7, 10.8, 9.9, 10.0),
+        ];
+
+        assert_eq!(
+            flags(short_trend(), &bars, "soldiers"),
+            vec![0.0, 0.0, 1.0, 0.0, 0.0, -1.0]
+        );
+    }
+
+    #[test]
+    fn test_inside_and_outside_bars() {
+        let bars = [
+            (10.0, 11.0, 9.0, 10.5),
+            (10.2, 10.8, 9.5, 10.4),
+            (10.4, 11.2, 9.2, 10.0),
+        ];
+
+        assert_eq!(flags(short_trend(), &bars, "inside"), vec![0.0, 1.0, 0.0]);
+        assert_eq!(flags(short_trend(), &bars, "outside"), vec![0.0, 0.0, 1.0]);
+    }
+}
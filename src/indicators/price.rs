@@ -0,0 +1,55 @@
+use crate::data_structures::kline::Kline;
+
+// The price of a bar an indicator is calculated on.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum PriceSource {
+    #[default]
+    Close,
+    HL2,
+    HLC3,
+    OHLC4,
+}
+
+impl PriceSource {
+    pub fn value(&self, kline: &Kline) -> f64 {
+        match self {
+            PriceSource::Close => kline.close,
+            PriceSource::HL2 => (kline.high + kline.low) / 2.0,
+            PriceSource::HLC3 => (kline.high + kline.low + kline.close) / 3.0,
+            PriceSource::OHLC4 => (kline.open + kline.high + kline.low + kline.close) / 4.0,
+        }
+    }
+
+    // Appended to indicator names; empty for the close.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            PriceSource::Close => "",
+            PriceSource::HL2 => "_hl2",
+            PriceSource::HLC3 => "_hlc3",
+            PriceSource::OHLC4 => "_ohlc4",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::kline::Kline;
+
+    use super::PriceSource;
+
+    #[test]
+    fn test_price_sources() {
+        let kline = Kline {
+            open: 10.0,
+            high: 14.0,
+            low: 6.0,
+            close: 12.0,
+            ..Default::default()
+        };
+
+        assert_eq!(PriceSource::Close.value(&kline), 12.0);
+        assert_eq!(PriceSource::HL2.value(&kline), 10.0);
+        assert_eq!(PriceSource::HLC3.value(&kline), 32.0 / 3.0);
+        assert_eq!(PriceSource::OHLC4.value(&kline), 10.5);
+    }
+}
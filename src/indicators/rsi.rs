@@ -1,6 +1,6 @@
-use crate::data_structures::history::History;
+use crate::data_structures::{history::History, kline::Kline};
 
-use super::Indicator;
+use super::{IncrementalIndicator, Indicator};
 
 #[derive(PartialEq, Eq, Hash, Clone)]
 pub struct RSIParams {
@@ -15,11 +15,73 @@ impl RSIParams {
 
 pub struct RSI {
     pub params: RSIParams,
+    // state as of the bar before the last one, kept to replace the last bar
+    previous: RSIState,
+    current: RSIState,
+}
+
+// Wilder's averages of gains and losses; until `period` changes were seen they
+// hold the running simple averages.
+#[derive(Clone, Default)]
+struct RSIState {
+    close: Option<f64>,
+    changes: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl RSIState {
+    fn step(&self, close: f64, period: usize) -> RSIState {
+        let Some(previous_close) = self.close else {
+            return RSIState {
+                close: Some(close),
+                ..Default::default()
+            };
+        };
+
+        let change = close - previous_close;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let changes = self.changes + 1;
+        let period = period as f64;
+
+        let (avg_gain, avg_loss) = match changes as f64 <= period {
+            true => (self.avg_gain + gain / period, self.avg_loss + loss / period),
+            false => (
+                ((self.avg_gain * (period - 1.0)) + gain) / period,
+                ((self.avg_loss * (period - 1.0)) + loss) / period,
+            ),
+        };
+
+        RSIState {
+            close: Some(close),
+            changes,
+            avg_gain,
+            avg_loss,
+        }
+    }
+
+    fn value(&self, period: usize) -> Option<f64> {
+        if self.changes < period {
+            return None;
+        }
+
+        let rs = if self.avg_loss == 0.0 {
+            100.0
+        } else {
+            self.avg_gain / self.avg_loss
+        };
+
+        Some(100.0 - (100.0 / (1.0 + rs)))
+    }
 }
 
 impl RSI {
     pub fn new(params: RSIParams) -> Self {
-        Self { params }
+        Self {
+            params,
+            previous: RSIState::default(),
+            current: RSIState::default(),
+        }
     }
 }
 
@@ -29,65 +91,50 @@ impl Indicator for RSI {
     }
 
     fn calculate(&self, history: &History) -> Vec<f64> {
-        let klines = history.last(self.params.period + 1);
+        let mut state = RSIState::default();
+
+        history
+            .window(history.len())
+            .close
+            .iter()
+            .filter_map(|close| {
+                state = state.step(*close, self.params.period);
+                state.value(self.params.period)
+            })
+            .collect()
+    }
 
-        let prices: Vec<f64> = klines.iter().map(|k| k.close).collect();
+    // one close more than the period, to have `period` changes
+    fn warmup(&self) -> usize {
+        self.params.period + 1
+    }
 
-        self.calculate_rsi_values(&prices)
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
     }
 }
 
-impl RSI {
-    fn calculate_rsi_values(&self, prices: &[f64]) -> Vec<f64> {
-        if prices.len() <= self.params.period {
-            return Vec::new();
+impl IncrementalIndicator for RSI {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        if !replace {
+            self.previous = self.current.clone();
         }
 
-        let changes: Vec<f64> = prices.windows(2).map(|window| window[1] - window[0]).collect();
-
-        let (gains, losses) = changes.iter().fold(
-            (Vec::with_capacity(changes.len()), Vec::with_capacity(changes.len())),
-            |(mut gains, mut losses), &change| {
-                if change >= 0.0 {
-                    gains.push(change);
-                    losses.push(0.0);
-                } else {
-                    gains.push(0.0);
-                    losses.push(change.abs());
-                }
-
-                (gains, losses)
-            },
-        );
-
-        let mut avg_gain = gains[0..self.params.period].iter().sum::<f64>() / self.params.period as f64;
-        let mut avg_loss = losses[0..self.params.period].iter().sum::<f64>() / self.params.period as f64;
-
-        let rs = if avg_loss == 0.0 { 100.0 } else { avg_gain / avg_loss };
-        let rsi = 100.0 - (100.0 / (1.0 + rs));
-
-        let mut rsi_values = Vec::new();
-
-        rsi_values.push(rsi);
-
-        for i in self.params.period..gains.len() {
-            avg_gain = ((avg_gain * (self.params.period as f64 - 1.0)) + gains[i]) / self.params.period as f64;
-            avg_loss = ((avg_loss * (self.params.period as f64 - 1.0)) + losses[i]) / self.params.period as f64;
-
-            let rs = if avg_loss == 0.0 { 100.0 } else { avg_gain / avg_loss };
-            let rsi = 100.0 - (100.0 / (1.0 + rs));
-            rsi_values.push(rsi);
-        }
+        self.current = self.previous.step(kline.close, self.params.period);
+        self.current.value(self.params.period).into_iter().collect()
+    }
 
-        rsi_values
+    fn reset(&mut self) {
+        self.previous = RSIState::default();
+        self.current = RSIState::default();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        data_structures::{history::History, kline::helpers::generate_klines_with_prices},
-        indicators::{rsi::RSIParams, Indicator},
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices, kline::Kline},
+        indicators::{rsi::RSIParams, IncrementalIndicator, Indicator},
     };
 
     use super::RSI;
@@ -132,4 +179,31 @@ mod tests {
         assert_eq!(result.len(), 1);
         assert!(result[0] < 30.0);
     }
+
+    #[test]
+    fn test_incremental_rsi_matches_calculation() {
+        let prices = (0..60)
+            .map(|i| 100.0 + ((i * 37) % 11) as f64 - (i % 4) as f64)
+            .collect::<Vec<f64>>();
+        let klines = generate_klines_with_prices(&prices);
+        let history = History::with_klines(klines.clone());
+        let mut rsi = RSI::new(RSIParams { period: 14 });
+
+        let expected = rsi.calculate(&history);
+        let values = klines
+            .iter()
+            .flat_map(|kline| {
+                // an unfinished version of every bar, replaced by the closed one
+                let partial = Kline {
+                    close: kline.close * 1.5,
+                    ..kline.clone()
+                };
+                rsi.update(&partial, false);
+                rsi.update(kline, true)
+            })
+            .collect::<Vec<f64>>();
+
+        assert_eq!(values.len(), 60 - 14);
+        assert_eq!(values, expected);
+    }
 }
@@ -0,0 +1,341 @@
+use std::collections::VecDeque;
+
+// Running averages over plain values, shared by the indicators built on them.
+// Every `update` either adds a value or, with `replace`, swaps the last one.
+
+pub struct RunningEma {
+    multiplier: f64,
+    // value as of the second to last input, kept to replace the last one
+    previous: Option<f64>,
+    current: Option<f64>,
+}
+
+impl RunningEma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            multiplier: 2.0 / (period as f64 + 1.0),
+            previous: None,
+            current: None,
+        }
+    }
+
+    pub fn update(&mut self, value: f64, replace: bool) -> f64 {
+        if !replace {
+            self.previous = self.current;
+        }
+
+        let ema = match self.previous {
+            None => value,
+            Some(previous) => ((value - previous) * self.multiplier) + previous,
+        };
+        self.current = Some(ema);
+
+        ema
+    }
+
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.current = None;
+    }
+}
+
+pub struct RunningSma {
+    period: usize,
+    values: VecDeque<f64>,
+    sum: f64,
+}
+
+impl RunningSma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            values: VecDeque::new(),
+            sum: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, value: f64, replace: bool) -> f64 {
+        match (replace, self.values.back_mut()) {
+            (true, Some(last)) => {
+                self.sum += value - *last;
+                *last = value;
+            }
+            _ => {
+                self.values.push_back(value);
+                self.sum += value;
+
+                if self.values.len() > self.period {
+                    self.sum -= self.values.pop_front().unwrap_or_default();
+                }
+            }
+        }
+
+        self.sum / self.values.len() as f64
+    }
+
+    pub fn reset(&mut self) {
+        self.values.clear();
+        self.sum = 0.0;
+    }
+}
+
+// Linearly weighted, the newest value weighing `period` and the oldest one.
+pub struct RunningWma {
+    period: usize,
+    values: VecDeque<f64>,
+    sum: f64,
+    weighted: f64,
+}
+
+impl RunningWma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            values: VecDeque::new(),
+            sum: 0.0,
+            weighted: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, value: f64, replace: bool) -> f64 {
+        let count = self.values.len();
+
+        if replace && count > 0 {
+            let change = value - self.values[count - 1];
+            self.values[count - 1] = value;
+            self.sum += change;
+            self.weighted += change * count as f64;
+        } else if count < self.period {
+            self.values.push_back(value);
+            self.sum += value;
+            self.weighted += value * (count + 1) as f64;
+        } else {
+            // every weight drops by one, the oldest value's to zero
+            self.weighted += value * self.period as f64 - self.sum;
+            self.sum += value - self.values.pop_front().unwrap_or_default();
+            self.values.push_back(value);
+        }
+
+        let count = self.values.len() as f64;
+        self.weighted / (count * (count + 1.0) / 2.0)
+    }
+
+    pub fn reset(&mut self) {
+        self.values.clear();
+        self.sum = 0.0;
+        self.weighted = 0.0;
+    }
+}
+
+// Mean and population variance of the last `period` values, kept with
+// Welford's method as values enter and leave the window. Unlike a running sum
+// of squares, large values do not cancel out.
+pub struct RunningVariance {
+    period: usize,
+    values: VecDeque<f64>,
+    mean: f64,
+    // sum of the squared distances from the mean
+    squares: f64,
+}
+
+impl RunningVariance {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            values: VecDeque::new(),
+            mean: 0.0,
+            squares: 0.0,
+        }
+    }
+
+    // Returns the mean and the variance of the window.
+    pub fn update(&mut self, value: f64, replace: bool) -> (f64, f64) {
+        if replace {
+            if let Some(last) = self.values.pop_back() {
+                self.remove(last);
+            }
+        }
+
+        self.values.push_back(value);
+        self.add(value);
+
+        if self.values.len() > self.period {
+            let first = self.values.pop_front().unwrap_or_default();
+            self.remove(first);
+        }
+
+        (self.mean, self.squares.max(0.0) / self.values.len() as f64)
+    }
+
+    fn add(&mut self, value: f64) {
+        let delta = value - self.mean;
+        self.mean += delta / self.values.len() as f64;
+        self.squares += delta * (value - self.mean);
+    }
+
+    fn remove(&mut self, value: f64) {
+        if self.values.is_empty() {
+            self.mean = 0.0;
+            self.squares = 0.0;
+            return;
+        }
+
+        let delta = value - self.mean;
+        self.mean -= delta / self.values.len() as f64;
+        self.squares -= delta * (value - self.mean);
+    }
+
+    pub fn reset(&mut self) {
+        self.values.clear();
+        self.mean = 0.0;
+        self.squares = 0.0;
+    }
+}
+
+// Wilder's smoothing: the simple average of the first `period` values, then
+// every value weighs 1/period.
+pub struct RunningWilder {
+    period: usize,
+    // state as of the second to last input, kept to replace the last one
+    previous: (usize, f64),
+    current: (usize, f64),
+}
+
+impl RunningWilder {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            previous: (0, 0.0),
+            current: (0, 0.0),
+        }
+    }
+
+    pub fn update(&mut self, value: f64, replace: bool) -> f64 {
+        if !replace {
+            self.previous = self.current;
+        }
+
+        let (count, average) = self.previous;
+        let count = count + 1;
+        let weight = count.min(self.period) as f64;
+        self.current = (count, average + (value - average) / weight);
+
+        self.current.1
+    }
+
+    // Whether the average covers `period` values.
+    pub fn is_ready(&self) -> bool {
+        self.current.0 >= self.period
+    }
+
+    pub fn reset(&mut self) {
+        self.previous = (0, 0.0);
+        self.current = (0, 0.0);
+    }
+}
+
+// The last `period` values, for the indicators looking at the extremes of a
+// window.
+pub struct RunningWindow {
+    period: usize,
+    values: VecDeque<f64>,
+}
+
+impl RunningWindow {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            values: VecDeque::new(),
+        }
+    }
+
+    pub fn update(&mut self, value: f64, replace: bool) {
+        if replace {
+            self.values.pop_back();
+        }
+
+        self.values.push_back(value);
+        if self.values.len() > self.period {
+            self.values.pop_front();
+        }
+    }
+
+    pub fn values(&self) -> &VecDeque<f64> {
+        &self.values
+    }
+
+    pub fn max(&self) -> f64 {
+        self.values.iter().copied().fold(f64::MIN, f64::max)
+    }
+
+    pub fn min(&self) -> f64 {
+        self.values.iter().copied().fold(f64::MAX, f64::min)
+    }
+
+    pub fn reset(&mut self) {
+        self.values.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RunningSma, RunningVariance, RunningWilder, RunningWma};
+
+    #[test]
+    fn test_sma_replaces_last_value() {
+        let mut sma = RunningSma::new(3);
+
+        let values = [1.0, 2.0, 3.0, 4.0].map(|value| sma.update(value, false));
+        let replaced = sma.update(10.0, true);
+
+        assert_eq!(values, [1.0, 1.5, 2.0, 3.0]);
+        assert_eq!(replaced, 5.0);
+        assert_eq!(sma.update(11.0, false), 8.0);
+    }
+
+    #[test]
+    fn test_wma_matches_direct_calculation() {
+        let prices = [3.0, 5.0, 4.0, 8.0, 6.0, 7.0, 2.0];
+        let mut wma = RunningWma::new(4);
+
+        for (index, price) in prices.iter().enumerate() {
+            wma.update(price + 100.0, false);
+            let value = wma.update(*price, true);
+
+            let window = &prices[(index + 1).saturating_sub(4)..=index];
+            let weights = (1..=window.len()).map(|w| w as f64);
+            let expected = window.iter().zip(weights.clone()).map(|(p, w)| p * w).sum::<f64>() / weights.sum::<f64>();
+
+            assert!((value - expected).abs() < 1e-9, "{} != {}", value, expected);
+        }
+    }
+
+    #[test]
+    fn test_variance_matches_direct_calculation() {
+        let prices = [3.0, 5.0, 4.0, 8.0, 6.0, 7.0, 2.0].map(|price| price + 1e5);
+        let mut variance = RunningVariance::new(4);
+
+        for (index, price) in prices.iter().enumerate() {
+            variance.update(price + 100.0, false);
+            let (mean, value) = variance.update(*price, true);
+
+            let window = &prices[(index + 1).saturating_sub(4)..=index];
+            let expected_mean = window.iter().sum::<f64>() / window.len() as f64;
+            let expected = window.iter().map(|p| (p - expected_mean).powi(2)).sum::<f64>() / window.len() as f64;
+
+            assert!((mean - expected_mean).abs() < 1e-6, "{} != {}", mean, expected_mean);
+            assert!((value - expected).abs() < 1e-6, "{} != {}", value, expected);
+        }
+    }
+
+    #[test]
+    fn test_wilder_seeds_with_simple_average() {
+        let mut wilder = RunningWilder::new(3);
+
+        let values = [3.0, 6.0, 9.0, 12.0].map(|value| wilder.update(value, false));
+        let replaced = wilder.update(3.0, true);
+
+        assert_eq!(values, [3.0, 4.5, 6.0, 8.0]);
+        assert_eq!(replaced, 5.0);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{price::PriceSource, running::RunningSma, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct SMAParams {
+    pub period: usize,
+    pub source: PriceSource,
+}
+
+impl SMAParams {
+    pub fn name(&self) -> String {
+        format!("sma_{}{}", self.period, self.source.suffix())
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+pub struct SMA {
+    pub params: SMAParams,
+    sma: RunningSma,
+}
+
+impl SMA {
+    pub fn new(params: SMAParams) -> Self {
+        SMA {
+            sma: RunningSma::new(params.period),
+            params,
+        }
+    }
+}
+
+impl Indicator for SMA {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(SMA::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for SMA {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let price = self.params.source.value(kline);
+
+        vec![self.sma.update(price, replace)]
+    }
+
+    fn reset(&mut self) {
+        self.sma.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{
+            history::History,
+            kline::{helpers::generate_klines_with_prices, Kline},
+        },
+        indicators::{
+            helpers::{assert_reference, PRICES},
+            price::PriceSource,
+            Indicator,
+        },
+    };
+
+    use super::{SMAParams, SMA};
+
+    #[test]
+    fn test_sma_reference_values() {
+        let history = History::with_klines(generate_klines_with_prices(&PRICES));
+        let sma = SMA::new(SMAParams {
+            period: 5,
+            source: PriceSource::Close,
+        });
+
+        let values = sma.calculate(&history);
+
+        assert_reference(
+            &values,
+            sma.warmup(),
+            &[
+                44.104, 44.202, 44.404, 44.658, 45.104, 45.454, 45.666, 45.852, 45.89, 45.978, 46.018, 46.04, 46.04,
+                46.2, 46.188, 46.06,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_sma_on_hl2() {
+        let klines = generate_klines_with_prices(&[0.0; 3])
+            .into_iter()
+            .enumerate()
+            .map(|(i, kline)| Kline {
+                high: 10.0 + i as f64,
+                low: 8.0 + i as f64,
+                ..kline
+            })
+            .collect::<Vec<Kline>>();
+        let history = History::with_klines(klines);
+        let params = SMAParams {
+            period: 3,
+            source: PriceSource::HL2,
+        };
+
+        assert_eq!(SMA::new(params.clone()).calculate(&history), vec![9.0, 9.5, 10.0]);
+        assert_eq!(params.name(), "sma_3_hl2");
+    }
+}
@@ -0,0 +1,144 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{
+    rsi::{RSIParams, RSI},
+    running::{RunningSma, RunningWindow},
+    series,
+    stochastic::position,
+    IncrementalIndicator, Indicator,
+};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct StochRSIParams {
+    pub rsi_period: usize,
+    pub period: usize,
+    pub smoothing: usize,
+    pub signal: usize,
+}
+
+impl StochRSIParams {
+    pub fn name(&self) -> String {
+        format!(
+            "stoch_rsi_{}_{}_{}_{}",
+            self.rsi_period, self.period, self.smoothing, self.signal
+        )
+    }
+}
+
+// The stochastic of the RSI: where the RSI sits within its range over the
+// last `period` values, as %K and its average %D. Nothing is produced until
+// the RSI is available.
+pub struct StochRSI {
+    pub params: StochRSIParams,
+    rsi: RSI,
+    values: RunningWindow,
+    k: RunningSma,
+    d: RunningSma,
+}
+
+impl StochRSI {
+    pub const OUTPUTS: [&'static str; 2] = ["k", "d"];
+
+    pub fn new(params: StochRSIParams) -> Self {
+        StochRSI {
+            rsi: RSI::new(RSIParams {
+                period: params.rsi_period,
+            }),
+            values: RunningWindow::new(params.period),
+            k: RunningSma::new(params.smoothing),
+            d: RunningSma::new(params.signal),
+            params,
+        }
+    }
+}
+
+impl Indicator for StochRSI {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(StochRSI::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.rsi_period + self.params.period + self.params.smoothing.max(1) + self.params.signal.max(1) - 2
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for StochRSI {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let Some(rsi) = self.rsi.update(kline, replace).first().copied() else {
+            return Vec::new();
+        };
+
+        self.values.update(rsi, replace);
+        let raw = position(rsi, self.values.min(), self.values.max());
+        let k = self.k.update(raw, replace);
+
+        vec![k, self.d.update(k, replace)]
+    }
+
+    fn reset(&mut self) {
+        IncrementalIndicator::reset(&mut self.rsi);
+        self.values.reset();
+        self.k.reset();
+        self.d.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            factory::Factory,
+            helpers::{assert_output_reference, bars},
+            IndicatorIdentifier,
+        },
+    };
+
+    use super::StochRSIParams;
+
+    #[test]
+    fn test_stoch_rsi_reference_values() {
+        let stoch_rsi = IndicatorIdentifier::StochRSI(StochRSIParams {
+            rsi_period: 5,
+            period: 5,
+            smoothing: 2,
+            signal: 2,
+        });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&stoch_rsi));
+
+        for kline in bars() {
+            history.insert(kline);
+        }
+
+        assert_eq!(Factory::create(&stoch_rsi).warmup(), 12);
+        assert_output_reference(
+            &history,
+            &stoch_rsi,
+            "k",
+            &[
+                30.8544, 14.2286, 29.3014, 68.6718, 46.2407, 18.8571, 60.0261, 56.5755, 8.5363,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &stoch_rsi,
+            "d",
+            &[
+                48.7401, 22.5415, 21.765, 48.9866, 57.4563, 32.5489, 39.4416, 58.3008, 32.5559,
+            ],
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{
+    running::{RunningSma, RunningWindow},
+    series, IncrementalIndicator, Indicator,
+};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct StochasticParams {
+    pub period: usize,
+    // bars the raw %K is averaged over, 1 for the fast stochastic
+    pub smoothing: usize,
+    pub signal: usize,
+}
+
+impl StochasticParams {
+    pub fn name(&self) -> String {
+        format!("stoch_{}_{}_{}", self.period, self.smoothing, self.signal)
+    }
+}
+
+// Where the close sits within the range of the last `period` bars, from 0 at
+// the lowest low to 100 at the highest high, as %K and its average %D.
+pub struct Stochastic {
+    pub params: StochasticParams,
+    highs: RunningWindow,
+    lows: RunningWindow,
+    k: RunningSma,
+    d: RunningSma,
+}
+
+impl Stochastic {
+    pub const OUTPUTS: [&'static str; 2] = ["k", "d"];
+
+    pub fn new(params: StochasticParams) -> Self {
+        Stochastic {
+            highs: RunningWindow::new(params.period),
+            lows: RunningWindow::new(params.period),
+            k: RunningSma::new(params.smoothing),
+            d: RunningSma::new(params.signal),
+            params,
+        }
+    }
+}
+
+// Position of `value` between `low` and `high` in percent, the middle when
+// they are equal.
+pub(super) fn position(value: f64, low: f64, high: f64) -> f64 {
+    match high > low {
+        true => 100.0 * (value - low) / (high - low),
+        false => 50.0,
+    }
+}
+
+impl Indicator for Stochastic {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(Stochastic::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period + self.params.smoothing.max(1) + self.params.signal.max(1) - 2
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for Stochastic {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        self.highs.update(kline.high, replace);
+        self.lows.update(kline.low, replace);
+
+        let raw = position(kline.close, self.lows.min(), self.highs.max());
+        let k = self.k.update(raw, replace);
+
+        vec![k, self.d.update(k, replace)]
+    }
+
+    fn reset(&mut self) {
+        self.highs.reset();
+        self.lows.reset();
+        self.k.reset();
+        self.d.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            factory::Factory,
+            helpers::{assert_output_reference, bars},
+            IndicatorIdentifier,
+        },
+    };
+
+    use super::StochasticParams;
+
+    #[test]
+    fn test_stochastic_reference_values() {
+        let stochastic = IndicatorIdentifier::Stochastic(StochasticParams {
+            period: 5,
+            smoothing: 3,
+            signal: 3,
+        });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&stochastic));
+
+        for kline in bars() {
+            history.insert(kline);
+        }
+
+        assert_eq!(Factory::create(&stochastic).warmup(), 9);
+        assert_output_reference(
+            &history,
+            &stochastic,
+            "k",
+            &[
+                86.9829, 87.5635, 82.5592, 78.3393, 60.6573, 61.4437, 60.7795, 66.3818, 59.2593, 60.114, 58.6895,
+                46.275,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &stochastic,
+            "d",
+            &[
+                84.1915, 86.4594, 85.7019, 82.8207, 73.852, 66.8135, 60.9602, 62.8683, 62.1402, 61.9183, 59.3542,
+                55.0261,
+            ],
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{
+    atr::{ATRParams, ATR},
+    factor::Factor,
+    price::PriceSource,
+    series, IncrementalIndicator, Indicator,
+};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct SuperTrendParams {
+    pub period: usize,
+    // distance of the bands from the median price in average true ranges
+    pub multiplier: Factor,
+}
+
+impl SuperTrendParams {
+    pub fn name(&self) -> String {
+        format!("supertrend_{}_{}", self.period, self.multiplier)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Bands {
+    upper: f64,
+    lower: f64,
+    close: f64,
+    rising: bool,
+}
+
+// A trailing stop following the price: the lower band while the trend rises
+// and the upper band while it falls. The bands only move towards the price
+// until the close crosses the active one, which flips the trend.
+pub struct SuperTrend {
+    pub params: SuperTrendParams,
+    atr: ATR,
+    // bands as of the bar before the last one, kept to replace the last bar
+    previous: Option<Bands>,
+    current: Option<Bands>,
+}
+
+impl SuperTrend {
+    pub const OUTPUTS: [&'static str; 2] = ["supertrend", "direction"];
+
+    pub fn new(params: SuperTrendParams) -> Self {
+        SuperTrend {
+            atr: ATR::new(ATRParams { period: params.period }),
+            previous: None,
+            current: None,
+            params,
+        }
+    }
+}
+
+impl Indicator for SuperTrend {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(SuperTrend::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for SuperTrend {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        if !replace {
+            self.previous = self.current;
+        }
+
+        let median = PriceSource::HL2.value(kline);
+        let width = self.params.multiplier.value() * self.atr.value(kline, replace);
+        let (upper, lower) = (median + width, median - width);
+
+        let bands = match self.previous {
+            None => Bands {
+                upper,
+                lower,
+                close: kline.close,
+                rising: true,
+            },
+            Some(previous) => {
+                let upper = match upper < previous.upper || previous.close > previous.upper {
+                    true => upper,
+                    false => previous.upper,
+                };
+                let lower = match lower > previous.lower || previous.close < previous.lower {
+                    true => lower,
+                    false => previous.lower,
+                };
+                let rising = match previous.rising {
+                    true => kline.close >= lower,
+                    false => kline.close > upper,
+                };
+
+                Bands {
+                    upper,
+                    lower,
+                    close: kline.close,
+                    rising,
+                }
+            }
+        };
+        self.current = Some(bands);
+
+        match bands.rising {
+            true => vec![bands.lower, 1.0],
+            false => vec![bands.upper, -1.0],
+        }
+    }
+
+    fn reset(&mut self) {
+        IncrementalIndicator::reset(&mut self.atr);
+        self.previous = None;
+        self.current = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            factor::Factor,
+            factory::Factory,
+            helpers::{assert_output_reference, bars},
+            IndicatorIdentifier,
+        },
+    };
+
+    use super::SuperTrendParams;
+
+    #[test]
+    fn test_supertrend_reference_values() {
+        let supertrend = IndicatorIdentifier::SuperTrend(SuperTrendParams {
+            period: 5,
+            multiplier: Factor(1.0),
+        });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&supertrend));
+
+        for kline in bars() {
+            history.insert(kline);
+        }
+
+        assert_eq!(Factory::create(&supertrend).warmup(), 5);
+        assert_output_reference(
+            &history,
+            &supertrend,
+            "supertrend",
+            &[
+                44.6425, 43.7788, 44.18, 44.478, 44.8304, 45.1823, 45.2349, 45.2419, 45.2419, 45.2419, 45.5318,
+                45.5318, 45.5318, 45.5318, 45.6115, 45.6115,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &supertrend,
+            "direction",
+            &[
+                -1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0,
+            ],
+        );
+    }
+}
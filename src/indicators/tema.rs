@@ -0,0 +1,97 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{price::PriceSource, running::RunningEma, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct TEMAParams {
+    pub period: usize,
+    pub source: PriceSource,
+}
+
+impl TEMAParams {
+    pub fn name(&self) -> String {
+        format!("tema_{}{}", self.period, self.source.suffix())
+    }
+}
+
+// Triple EMA: 3 * EMA - 3 * EMA(EMA) + EMA(EMA(EMA)).
+#[allow(clippy::upper_case_acronyms)]
+pub struct TEMA {
+    pub params: TEMAParams,
+    emas: [RunningEma; 3],
+}
+
+impl TEMA {
+    pub fn new(params: TEMAParams) -> Self {
+        assert!(params.period > 0, "TEMA period has to be positive");
+
+        TEMA {
+            emas: [0; 3].map(|_| RunningEma::new(params.period)),
+            params,
+        }
+    }
+}
+
+impl Indicator for TEMA {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(TEMA::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        3 * self.params.period - 2
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for TEMA {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let price = self.params.source.value(kline);
+        let first = self.emas[0].update(price, replace);
+        let second = self.emas[1].update(first, replace);
+        let third = self.emas[2].update(second, replace);
+
+        vec![3.0 * first - 3.0 * second + third]
+    }
+
+    fn reset(&mut self) {
+        self.emas.iter_mut().for_each(RunningEma::reset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices},
+        indicators::{
+            helpers::{assert_reference, PRICES},
+            price::PriceSource,
+            Indicator,
+        },
+    };
+
+    use super::{TEMAParams, TEMA};
+
+    #[test]
+    fn test_tema_reference_values() {
+        let history = History::with_klines(generate_klines_with_prices(&PRICES));
+        let tema = TEMA::new(TEMAParams {
+            period: 5,
+            source: PriceSource::Close,
+        });
+
+        let values = tema.calculate(&history);
+
+        assert_reference(
+            &values,
+            tema.warmup(),
+            &[45.8196, 46.1371, 46.2659, 46.1089, 46.0495, 46.2877, 46.2543, 45.8241],
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{series, IncrementalIndicator, Indicator};
+
+// The true range: the high to low range of a bar extended to the previous
+// close when the bar gapped away from it.
+#[derive(Default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct TR {
+    // close of the bar before the last one, kept to replace the last bar
+    previous_close: Option<f64>,
+    last_close: Option<f64>,
+}
+
+impl TR {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn range(&mut self, kline: &Kline, replace: bool) -> f64 {
+        if !replace {
+            self.previous_close = self.last_close;
+        }
+        self.last_close = Some(kline.close);
+
+        match self.previous_close {
+            Some(close) => kline.high.max(close) - kline.low.min(close),
+            None => kline.high - kline.low,
+        }
+    }
+}
+
+impl Indicator for TR {
+    fn name(&self) -> String {
+        "tr".to_string()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(TR::new(), history)
+    }
+
+    fn warmup(&self) -> usize {
+        1
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for TR {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        vec![self.range(kline, replace)]
+    }
+
+    fn reset(&mut self) {
+        self.previous_close = None;
+        self.last_close = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            helpers::{assert_reference, bars},
+            Indicator,
+        },
+    };
+
+    use super::TR;
+
+    #[test]
+    fn test_tr_reference_values() {
+        let history = History::with_klines(bars());
+
+        assert_reference(
+            &TR::new().calculate(&history),
+            1,
+            &[
+                0.5, 0.75, 0.56, 1.04, 1.22, 1.0, 0.77, 0.82, 0.92, 0.74, 0.69, 0.64, 0.92, 1.17, 0.5, 0.78, 0.53,
+                0.88, 0.69, 1.08,
+            ],
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::VecDeque;
+
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{series, IncrementalIndicator, Indicator};
+
+// Share of the volume around the point of control making up the value area.
+const VALUE_AREA: f64 = 0.7;
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct VolumeProfileParams {
+    pub period: usize,
+    pub buckets: usize,
+}
+
+impl VolumeProfileParams {
+    pub fn name(&self) -> String {
+        format!("vp_{}_{}", self.period, self.buckets)
+    }
+}
+
+// The volume traded at each price over the last bars, split into equal price
+// buckets between their lowest low and highest high. A bar's volume is spread
+// evenly over its range.
+pub struct Profile {
+    pub low: f64,
+    pub width: f64,
+    pub volumes: Vec<f64>,
+}
+
+impl Profile {
+    pub fn new(bars: impl Iterator<Item = (f64, f64, f64)> + Clone, buckets: usize) -> Self {
+        let buckets = buckets.max(1);
+        let low = bars.clone().map(|(_, low, _)| low).fold(f64::MAX, f64::min);
+        let high = bars.clone().map(|(high, _, _)| high).fold(f64::MIN, f64::max);
+        let width = (high - low).max(0.0) / buckets as f64;
+        let mut volumes = vec![0.0; buckets];
+
+        for (high, bottom, volume) in bars {
+            if high <= bottom || width == 0.0 {
+                let index = if width > 0.0 {
+                    ((bottom - low) / width) as usize
+                } else {
+                    0
+                };
+                volumes[index.min(buckets - 1)] += volume;
+                continue;
+            }
+
+            for (index, bucket) in volumes.iter_mut().enumerate() {
+                let start = low + index as f64 * width;
+                let overlap = (start + width).min(high) - start.max(bottom);
+
+                if overlap > 0.0 {
+                    *bucket += volume * overlap / (high - bottom);
+                }
+            }
+        }
+
+        Profile { low, width, volumes }
+    }
+
+    // Index of the bucket with the most volume, the lowest one on a tie.
+    pub fn point_of_control(&self) -> usize {
+        (0..self.volumes.len()).fold(0, |best, index| match self.volumes[index] > self.volumes[best] {
+            true => index,
+            false => best,
+        })
+    }
+
+    // Range of buckets holding `VALUE_AREA` of the volume, grown from the
+    // point of control towards the side with more volume.
+    pub fn value_area(&self) -> (usize, usize) {
+        let total = self.volumes.iter().sum::<f64>();
+        let poc = self.point_of_control();
+        let (mut first, mut last, mut volume) = (poc, poc, self.volumes[poc]);
+
+        while volume < VALUE_AREA * total {
+            let above = self.volumes.get(last + 1).copied();
+            let below = first.checked_sub(1).map(|index| self.volumes[index]);
+
+            match (above, below) {
+                (Some(above), Some(below)) if below > above => {
+                    first -= 1;
+                    volume += below;
+                }
+                (Some(above), _) => {
+                    last += 1;
+                    volume += above;
+                }
+                (None, Some(below)) => {
+                    first -= 1;
+                    volume += below;
+                }
+                (None, None) => break,
+            }
+        }
+
+        (first, last)
+    }
+
+    pub fn price(&self, index: usize) -> f64 {
+        self.low + (index as f64 + 0.5) * self.width
+    }
+}
+
+// The point of control and the high and low of the value area of the
+// profile over the last `period` bars.
+pub struct VolumeProfile {
+    pub params: VolumeProfileParams,
+    bars: VecDeque<(f64, f64, f64)>,
+}
+
+impl VolumeProfile {
+    pub const OUTPUTS: [&'static str; 3] = ["poc", "value_area_high", "value_area_low"];
+
+    pub fn new(params: VolumeProfileParams) -> Self {
+        VolumeProfile {
+            params,
+            bars: VecDeque::new(),
+        }
+    }
+}
+
+impl Indicator for VolumeProfile {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(VolumeProfile::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &Self::OUTPUTS
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for VolumeProfile {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        if replace {
+            self.bars.pop_back();
+        }
+
+        self.bars.push_back((kline.high, kline.low, kline.volume));
+        if self.bars.len() > self.params.period.max(1) {
+            self.bars.pop_front();
+        }
+
+        let profile = Profile::new(self.bars.iter().copied(), self.params.buckets);
+        let (first, last) = profile.value_area();
+
+        vec![
+            profile.price(profile.point_of_control()),
+            profile.low + (last + 1) as f64 * profile.width,
+            profile.low + first as f64 * profile.width,
+        ]
+    }
+
+    fn reset(&mut self) {
+        self.bars.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            factory::Factory,
+            helpers::{assert_output_reference, bars},
+            IndicatorIdentifier,
+        },
+    };
+
+    use super::{Profile, VolumeProfileParams};
+
+    #[test]
+    fn test_volume_profile_reference_values() {
+        let profile = IndicatorIdentifier::VolumeProfile(VolumeProfileParams { period: 10, buckets: 5 });
+        let mut history = History::new();
+        history.request_calculators(std::slice::from_ref(&profile));
+
+        for kline in bars() {
+            history.insert(kline);
+        }
+
+        assert_eq!(Factory::create(&profile).warmup(), 10);
+        assert_output_reference(
+            &history,
+            &profile,
+            "poc",
+            &[
+                44.301, 44.301, 46.083, 46.083, 45.629, 45.845, 45.995, 46.076, 45.965, 46.06, 46.06,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &profile,
+            "value_area_high",
+            &[
+                45.786, 46.38, 46.38, 46.38, 46.58, 46.58, 46.58, 46.58, 46.412, 46.45, 46.45,
+            ],
+        );
+        assert_output_reference(
+            &history,
+            &profile,
+            "value_area_low",
+            &[
+                44.004, 44.004, 44.004, 44.598, 44.678, 45.11, 45.41, 45.572, 45.518, 45.67, 45.67,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_profile_volumes() {
+        let bars = bars();
+        let last = bars[bars.len() - 10..]
+            .iter()
+            .map(|kline| (kline.high, kline.low, kline.volume));
+        let profile = Profile::new(last, 5);
+
+        let expected = [926.1675, 2298.2241, 3644.5342, 3520.8218, 1610.2524];
+        for (volume, expected) in profile.volumes.iter().zip(expected) {
+            assert!((volume - expected).abs() < 1e-4);
+        }
+        assert_eq!(profile.point_of_control(), 2);
+    }
+}
@@ -0,0 +1,186 @@
+use chrono::{DateTime, Utc};
+
+use crate::data_structures::{history::History, interval::Interval, kline::Kline};
+
+use super::{price::PriceSource, running::RunningSma, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub enum VWAPAnchor {
+    // restarts with every bucket of the interval, e.g. every day
+    Session(Interval),
+    // over the last number of bars
+    Rolling(usize),
+}
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct VWAPParams {
+    pub anchor: VWAPAnchor,
+    pub source: PriceSource,
+}
+
+impl VWAPParams {
+    pub fn name(&self) -> String {
+        match &self.anchor {
+            VWAPAnchor::Session(interval) => format!("vwap_{}{}", interval, self.source.suffix()),
+            VWAPAnchor::Rolling(period) => format!("vwap_rolling_{}{}", period, self.source.suffix()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Session {
+    start: Option<DateTime<Utc>>,
+    weighted: f64,
+    volume: f64,
+}
+
+// The volume weighted average price. Bars without volume leave it at the
+// price until some volume was traded.
+#[allow(clippy::upper_case_acronyms)]
+pub struct VWAP {
+    pub params: VWAPParams,
+    // session as of the bar before the last one, kept to replace the last bar
+    previous: Session,
+    current: Session,
+    weighted: RunningSma,
+    volume: RunningSma,
+}
+
+impl VWAP {
+    pub fn new(params: VWAPParams) -> Self {
+        let period = match params.anchor {
+            VWAPAnchor::Rolling(period) => period,
+            VWAPAnchor::Session(_) => 1,
+        };
+
+        VWAP {
+            params,
+            previous: Session::default(),
+            current: Session::default(),
+            weighted: RunningSma::new(period),
+            volume: RunningSma::new(period),
+        }
+    }
+}
+
+impl Indicator for VWAP {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(VWAP::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        match self.params.anchor {
+            VWAPAnchor::Rolling(period) => period,
+            VWAPAnchor::Session(_) => 1,
+        }
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for VWAP {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let price = self.params.source.value(kline);
+
+        let (weighted, volume) = match self.params.anchor {
+            VWAPAnchor::Session(interval) => {
+                if !replace {
+                    self.previous = self.current;
+                }
+
+                let start = Some(interval.bucket_start(kline.time));
+                let session = match self.previous.start == start {
+                    true => self.previous,
+                    false => Session::default(),
+                };
+                self.current = Session {
+                    start,
+                    weighted: session.weighted + price * kline.volume,
+                    volume: session.volume + kline.volume,
+                };
+
+                (self.current.weighted, self.current.volume)
+            }
+            // the ratio of the averages is the ratio of the sums
+            VWAPAnchor::Rolling(_) => (
+                self.weighted.update(price * kline.volume, replace),
+                self.volume.update(kline.volume, replace),
+            ),
+        };
+
+        match volume > 0.0 {
+            true => vec![weighted / volume],
+            false => vec![price],
+        }
+    }
+
+    fn reset(&mut self) {
+        self.previous = Session::default();
+        self.current = Session::default();
+        self.weighted.reset();
+        self.volume.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::{
+        data_structures::{
+            history::History,
+            interval::Interval,
+            kline::{helpers::generate_klines_with_interval, Kline},
+        },
+        indicators::{
+            helpers::{assert_reference, bars},
+            price::PriceSource,
+            Indicator,
+        },
+    };
+
+    use super::{VWAPAnchor, VWAPParams, VWAP};
+
+    #[test]
+    fn test_rolling_vwap_reference_values() {
+        let history = History::with_klines(bars());
+        let vwap = VWAP::new(VWAPParams {
+            anchor: VWAPAnchor::Rolling(5),
+            source: PriceSource::HLC3,
+        });
+
+        assert_reference(
+            &vwap.calculate(&history),
+            vwap.warmup(),
+            &[
+                44.1233, 44.1772, 44.3306, 44.5672, 44.9811, 45.4267, 45.6417, 45.8202, 45.9075, 45.9848, 46.0501,
+                46.0734, 46.0802, 46.1868, 46.2359, 46.1317,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_session_vwap_restarts_every_day() {
+        // four hourly bars before midnight and two after
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        let klines = generate_klines_with_interval(start, &[10.0, 20.0, 30.0, 40.0, 50.0, 60.0], 3600)
+            .into_iter()
+            .zip([1.0, 1.0, 2.0, 0.0, 3.0, 1.0])
+            .map(|(kline, volume)| Kline { volume, ..kline })
+            .collect::<Vec<Kline>>();
+        let vwap = VWAP::new(VWAPParams {
+            anchor: VWAPAnchor::Session(Interval::Days(1)),
+            source: PriceSource::Close,
+        });
+
+        let values = vwap.calculate(&History::with_klines(klines));
+
+        assert_eq!(values, vec![10.0, 15.0, 22.5, 22.5, 50.0, 52.5]);
+    }
+}
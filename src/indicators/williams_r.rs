@@ -0,0 +1,93 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{running::RunningWindow, series, stochastic::position, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct WilliamsRParams {
+    pub period: usize,
+}
+
+impl WilliamsRParams {
+    pub fn name(&self) -> String {
+        format!("williams_r_{}", self.period)
+    }
+}
+
+// Williams %R: the distance of the close below the highest high of the last
+// `period` bars relative to their range, from 0 at the high to -100 at the
+// low.
+pub struct WilliamsR {
+    pub params: WilliamsRParams,
+    highs: RunningWindow,
+    lows: RunningWindow,
+}
+
+impl WilliamsR {
+    pub fn new(params: WilliamsRParams) -> Self {
+        WilliamsR {
+            highs: RunningWindow::new(params.period),
+            lows: RunningWindow::new(params.period),
+            params,
+        }
+    }
+}
+
+impl Indicator for WilliamsR {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(WilliamsR::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for WilliamsR {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        self.highs.update(kline.high, replace);
+        self.lows.update(kline.low, replace);
+
+        vec![position(kline.close, self.lows.min(), self.highs.max()) - 100.0]
+    }
+
+    fn reset(&mut self) {
+        self.highs.reset();
+        self.lows.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::history::History,
+        indicators::{
+            helpers::{assert_reference, bars},
+            Indicator,
+        },
+    };
+
+    use super::{WilliamsR, WilliamsRParams};
+
+    #[test]
+    fn test_williams_r_reference_values() {
+        let history = History::with_klines(bars());
+        let williams_r = WilliamsR::new(WilliamsRParams { period: 5 });
+
+        assert_reference(
+            &williams_r.calculate(&history),
+            williams_r.warmup(),
+            &[
+                -25.2033, -17.4419, -15.0754, -12.987, -10.989, -13.3333, -28.0, -23.6486, -66.3793, -25.641, -25.641,
+                -49.5726, -47.0085, -23.0769, -53.8462, -84.252,
+            ],
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use crate::data_structures::{history::History, kline::Kline};
+
+use super::{price::PriceSource, running::RunningWma, series, IncrementalIndicator, Indicator};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct WMAParams {
+    pub period: usize,
+    pub source: PriceSource,
+}
+
+impl WMAParams {
+    pub fn name(&self) -> String {
+        format!("wma_{}{}", self.period, self.source.suffix())
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+pub struct WMA {
+    pub params: WMAParams,
+    wma: RunningWma,
+}
+
+impl WMA {
+    pub fn new(params: WMAParams) -> Self {
+        WMA {
+            wma: RunningWma::new(params.period),
+            params,
+        }
+    }
+}
+
+impl Indicator for WMA {
+    fn name(&self) -> String {
+        self.params.name()
+    }
+
+    fn calculate(&self, history: &History) -> Vec<f64> {
+        series(WMA::new(self.params.clone()), history)
+    }
+
+    fn warmup(&self) -> usize {
+        self.params.period
+    }
+
+    fn incremental(&mut self) -> &mut dyn IncrementalIndicator {
+        self
+    }
+}
+
+impl IncrementalIndicator for WMA {
+    fn update(&mut self, kline: &Kline, replace: bool) -> Vec<f64> {
+        let price = self.params.source.value(kline);
+
+        vec![self.wma.update(price, replace)]
+    }
+
+    fn reset(&mut self) {
+        self.wma.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices},
+        indicators::{
+            helpers::{assert_reference, PRICES},
+            price::PriceSource,
+            Indicator,
+        },
+    };
+
+    use super::{WMAParams, WMA};
+
+    #[test]
+    fn test_wma_reference_values() {
+        let history = History::with_klines(generate_klines_with_prices(&PRICES));
+        let wma = WMA::new(WMAParams {
+            period: 5,
+            source: PriceSource::Close,
+        });
+
+        let values = wma.calculate(&history);
+
+        assert_reference(
+            &values,
+            wma.warmup(),
+            &[
+                44.0707, 44.3127, 44.612, 44.9507, 45.3447, 45.67, 45.8153, 45.9367, 45.856, 45.986, 46.0867, 46.0807,
+                46.0773, 46.2007, 46.2073, 46.0247,
+            ],
+        );
+    }
+}
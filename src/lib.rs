@@ -0,0 +1,8 @@
+pub mod connectors;
+pub mod data_structures;
+pub mod engines;
+pub mod indicators;
+pub mod processor;
+pub mod signal_processors;
+pub mod source;
+pub mod strategies;
@@ -1,27 +1,27 @@
-mod connectors;
-mod data_structures;
-mod engines;
-mod indicators;
-mod processor;
-mod signal_processors;
-mod source;
-mod strategies;
+use std::path::PathBuf;
 
-use connectors::binance::Binance;
-use processor::{Processor, ProcessorMode};
-use signal_processors::SignalProcessor;
+use cerunnos::{
+    connectors::binance::Binance,
+    processor::{Processor, ProcessorMode},
+    signal_processors::{self, SignalProcessor},
+    source::cache::Cache,
+};
+use chrono::{TimeDelta, Utc};
+
+const CACHE_DIRECTORY: &str = ".cache/klines";
 
 #[tokio::main]
 async fn main() {
     env_logger::init();
 
-    let source = Box::new(Binance::new("BTCUSDT".to_string(), "1m".to_string()).await);
+    let binance = Box::new(Binance::new("BTCUSDT".to_string(), "1m".to_string()).await);
+    let end = Utc::now();
+    let source =
+        Box::new(Cache::new(binance, PathBuf::from(CACHE_DIRECTORY)).with_range(end - TimeDelta::days(7), end));
     let logging_signal_processor = Box::new(signal_processors::logging::Logging::new());
     let backtest_signal_processor = Box::new(signal_processors::backtest::Backtest::new(1000.0, 0.1, 0.05));
-    let mut signal_processors: Vec<Box<dyn SignalProcessor>> = Vec::new();
+    let signal_processors: Vec<Box<dyn SignalProcessor>> = vec![logging_signal_processor, backtest_signal_processor];
 
-    signal_processors.push(logging_signal_processor);
-    signal_processors.push(backtest_signal_processor);
     let mut processor = Processor::new(source, signal_processors);
 
     processor
@@ -1,6 +1,9 @@
+pub mod portfolio;
+
 use crate::data_structures::history::History;
 use crate::data_structures::kline::Kline;
 use crate::indicators::ema::EMAParams;
+use crate::indicators::price::PriceSource;
 use crate::indicators::IndicatorIdentifier;
 use crate::signal_processors::backtest::Backtest;
 use crate::signal_processors::SignalProcessor;
@@ -8,13 +11,21 @@ use crate::source::{Result, Source};
 use crate::strategies::crossover::PriceCrossOverStrategy;
 use crate::strategies::Strategy;
 use futures::stream::{self, StreamExt};
-use log::error;
+use log::{error, warn};
 
 pub struct Processor {
     history: History,
     strategies: Vec<Box<dyn Strategy>>,
     source: Box<dyn Source>,
     signal_processors: Vec<Box<dyn SignalProcessor>>,
+    evaluation: Evaluation,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Evaluation {
+    #[default]
+    OnClose,
+    OnTick,
 }
 
 enum StrategyOption {
@@ -30,8 +41,14 @@ pub enum ProcessorMode {
 impl Processor {
     pub fn new(source: Box<dyn Source>, signal_processors: Vec<Box<dyn SignalProcessor>>) -> Self {
         let mut strategies = Vec::new();
-        let mut history = History::new();
-        let ema_20 = IndicatorIdentifier::EMA(EMAParams { period: 20 });
+        let mut history = match source.timeframe().parse() {
+            Ok(interval) => History::new().with_interval(interval),
+            Err(_) => History::new(),
+        };
+        let ema_20 = IndicatorIdentifier::EMA(EMAParams {
+            period: 20,
+            source: PriceSource::Close,
+        });
 
         let price_ema_crossover = PriceCrossOverStrategy::new("EMAPriceCrossOver".to_string(), ema_20.clone());
 
@@ -45,14 +62,37 @@ impl Processor {
                 .as_slice(),
         );
 
+        for (interval, indicators) in strategies.iter().flat_map(|strategy| strategy.request_timeframes()) {
+            match history.interval() {
+                Some(_) => history.add_timeframe(interval).request_calculators(&indicators),
+                None => warn!(
+                    "unknown timeframe {}, {} klines will not be built",
+                    source.timeframe(),
+                    interval
+                ),
+            }
+        }
+
         Self {
             source,
             history,
             strategies,
             signal_processors,
+            evaluation: Evaluation::default(),
         }
     }
 
+    pub fn with_evaluation(mut self, evaluation: Evaluation) -> Self {
+        self.evaluation = evaluation;
+        self
+    }
+
+    // Bounds the history kept while running live; see `History::with_retention`.
+    pub fn with_retention(mut self, bars: usize) -> Self {
+        self.history = std::mem::take(&mut self.history).with_retention(bars);
+        self
+    }
+
     pub async fn start(&mut self, mode: ProcessorMode) -> Result<()> {
         match self.source.fetch_history().await {
             Ok(klines) => {
@@ -90,8 +130,10 @@ impl Processor {
         while let Some(event) = stream.next().await {
             match event {
                 Ok(kline) => {
+                    let evaluate = kline.closed || self.evaluation == Evaluation::OnTick;
+
                     self.history.insert(kline);
-                    if let StrategyOption::Apply = strategy {
+                    if let (StrategyOption::Apply, true) = (&strategy, evaluate) {
                         self.apply_strategies().await
                     }
                 }
@@ -104,6 +146,10 @@ impl Processor {
 
     async fn apply_strategies(&mut self) {
         for strategy in &self.strategies {
+            if !strategy.is_ready(&self.history) {
+                continue;
+            }
+
             let signals = strategy.generate_signals(&self.history);
 
             for signal in signals {
@@ -124,3 +170,93 @@ impl Processor {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::{TimeDelta, Utc};
+
+    use crate::{
+        data_structures::{kline::helpers::generate_klines_with_interval, signal::Signal},
+        signal_processors::helpers::Recorder,
+        source::helpers::StaticSource,
+    };
+
+    use super::*;
+
+    fn processor(evaluation: Evaluation) -> (Processor, Arc<Mutex<Vec<Signal>>>) {
+        let start = Utc::now() - TimeDelta::hours(1);
+        let klines = generate_klines_with_interval(start, &[100.0; 30], 60);
+        let (history, last) = klines.split_at(29);
+
+        // three partial updates of the last bar followed by its close
+        let live = [101.0, 99.0, 102.0, 103.0]
+            .iter()
+            .enumerate()
+            .map(|(i, price)| Kline {
+                close: *price,
+                closed: i == 3,
+                ..last[0].clone()
+            })
+            .collect();
+
+        let signals = Arc::new(Mutex::new(Vec::new()));
+        let source = Box::new(StaticSource {
+            history: history.to_vec(),
+            live,
+        });
+        let recorder = Box::new(Recorder {
+            signals: signals.clone(),
+        });
+
+        (
+            Processor::new(source, vec![recorder]).with_evaluation(evaluation),
+            signals,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_live_evaluates_on_close_only() {
+        let (mut processor, signals) = processor(Evaluation::OnClose);
+
+        processor.start(ProcessorMode::Live).await.unwrap();
+
+        let signals = signals.lock().unwrap();
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].price, 103.0);
+        assert_eq!(processor.history.len(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_live_evaluates_on_every_tick() {
+        let (mut processor, signals) = processor(Evaluation::OnTick);
+
+        processor.start(ProcessorMode::Live).await.unwrap();
+
+        let prices = signals.lock().unwrap().iter().map(|s| s.price).collect::<Vec<f64>>();
+        assert_eq!(prices, vec![101.0, 99.0, 102.0, 103.0]);
+        assert_eq!(processor.history.len(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_signals_are_suppressed_until_warmed_up() {
+        let klines = generate_klines_with_interval(Utc::now() - TimeDelta::hours(1), &[100.0; 25], 60);
+        let signals = Arc::new(Mutex::new(Vec::new()));
+        let source = Box::new(StaticSource {
+            history: klines.clone(),
+            live: Vec::new(),
+        });
+        let recorder = Box::new(Recorder {
+            signals: signals.clone(),
+        });
+
+        let mut processor = Processor::new(source, vec![recorder]);
+        processor.start(ProcessorMode::Backtest).await.unwrap();
+
+        // EMA(20) is ready at the 20th bar and the crossover compares two bars
+        let signals = signals.lock().unwrap();
+        assert_eq!(signals.len(), 5);
+        assert_eq!(signals[0].time, klines[20].time);
+    }
+}
@@ -0,0 +1,498 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    pin::Pin,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::{
+    future::join_all,
+    stream::{self, select_all},
+    Stream, StreamExt,
+};
+use log::{error, info, trace, warn};
+
+use crate::{
+    data_structures::{history::History, kline::Kline, universe::Universe},
+    signal_processors::{backtest::Backtest, SignalProcessor},
+    source::{Result, Source},
+    strategies::{PortfolioStrategy, Strategy},
+};
+
+use super::{Processor, ProcessorMode};
+
+// A kline tagged with the symbol of the source it came from; `None` marks the
+// end of that source's stream.
+type Event = (String, Option<Result<Kline>>);
+type EventStream = Pin<Box<dyn Stream<Item = Event> + Send>>;
+
+// Runs strategies over several sources at once, one history per symbol.
+// Closed bars are held back until every symbol has reached their open time, so
+// strategies always see the universe at a single point in time.
+pub struct PortfolioProcessor {
+    universe: Universe,
+    sources: Vec<Box<dyn Source>>,
+    strategies: Vec<Box<dyn Strategy>>,
+    portfolio_strategies: Vec<Box<dyn PortfolioStrategy>>,
+    signal_processors: Vec<Box<dyn SignalProcessor>>,
+}
+
+impl PortfolioProcessor {
+    // One source per symbol; higher timeframes of a symbol are built from its
+    // history through `Strategy::request_timeframes`.
+    pub fn new(sources: Vec<Box<dyn Source>>, signal_processors: Vec<Box<dyn SignalProcessor>>) -> Result<Self> {
+        let mut universe = Universe::new();
+
+        for source in &sources {
+            if universe.history(source.symbol()).is_some() {
+                return Err(format!("more than one source for {}", source.symbol()).into());
+            }
+
+            let history = match source.timeframe().parse() {
+                Ok(interval) => History::new().with_interval(interval),
+                Err(_) => History::new(),
+            };
+            universe.add(source.symbol(), history);
+        }
+
+        Ok(Self {
+            universe,
+            sources,
+            strategies: Vec::new(),
+            portfolio_strategies: Vec::new(),
+            signal_processors,
+        })
+    }
+
+    // Adds a strategy evaluated on the history of every symbol.
+    pub fn with_strategy(mut self, strategy: Box<dyn Strategy>) -> Self {
+        let symbols = self.universe.symbols().map(String::from).collect::<Vec<String>>();
+
+        for symbol in symbols {
+            if let Some(history) = self.universe.history_mut(&symbol) {
+                history.request_calculators(&strategy.request_indicators());
+
+                for (interval, indicators) in strategy.request_timeframes() {
+                    if history.interval().is_some() {
+                        history.add_timeframe(interval).request_calculators(&indicators);
+                    }
+                }
+            }
+        }
+
+        self.strategies.push(strategy);
+        self
+    }
+
+    pub fn with_portfolio_strategy(mut self, strategy: Box<dyn PortfolioStrategy>) -> Self {
+        for (symbol, indicator) in strategy.request_indicators() {
+            match self.universe.history_mut(&symbol) {
+                Some(history) => history.request_calculators(&[indicator]),
+                None => warn!("{} requested indicators of unknown symbol {}", strategy.name(), symbol),
+            }
+        }
+
+        self.portfolio_strategies.push(strategy);
+        self
+    }
+
+    pub fn universe(&self) -> &Universe {
+        &self.universe
+    }
+
+    pub async fn start(&mut self, mode: ProcessorMode) -> Result<()> {
+        let histories = join_all(self.sources.iter().map(|source| source.fetch_history())).await;
+        let events = self
+            .sources
+            .iter()
+            .zip(histories)
+            .map(|(source, history)| {
+                let klines = history.unwrap_or_else(|e| {
+                    error!("{}: {}", source.symbol(), e);
+                    Vec::new()
+                });
+
+                Self::tagged(source.symbol(), stream::iter(klines.into_iter().map(Ok)))
+            })
+            .collect::<Vec<EventStream>>();
+
+        let apply = matches!(mode, ProcessorMode::Backtest);
+        self.consume(select_all(events), apply, None).await;
+
+        match mode {
+            ProcessorMode::Live => {
+                let events = self
+                    .sources
+                    .iter()
+                    .map(|source| Self::tagged(source.symbol(), source.fetch_live()))
+                    .collect::<Vec<EventStream>>();
+
+                // live bars of every symbol close together, one that is a
+                // whole interval late is not waited for
+                let patience = self
+                    .universe
+                    .symbols()
+                    .filter_map(|symbol| self.universe.history(symbol)?.interval())
+                    .map(|interval| interval.duration())
+                    .max();
+
+                self.consume(select_all(events), true, patience).await;
+                Ok(())
+            }
+            ProcessorMode::Backtest => {
+                if let Some(backtest) = Processor::get_signal_processor_by_type::<Backtest>(&mut self.signal_processors)
+                {
+                    backtest.get_performance_metrics().print_summary();
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn tagged<S>(symbol: &str, klines: S) -> EventStream
+    where
+        S: Stream<Item = Result<Kline>> + Send + 'static,
+    {
+        let symbol = symbol.to_string();
+        let end = symbol.clone();
+
+        Box::pin(
+            klines
+                .map(move |kline| (symbol.clone(), Some(kline)))
+                .chain(stream::once(async move { (end, None) })),
+        )
+    }
+
+    async fn consume<S>(&mut self, events: S, apply: bool, patience: Option<TimeDelta>)
+    where
+        S: Stream<Item = Event>,
+    {
+        let mut synchronizer = Synchronizer::new(self.universe.symbols()).with_patience(patience);
+        tokio::pin!(events);
+
+        while let Some((symbol, event)) = events.next().await {
+            let ready = match event {
+                Some(Ok(kline)) => synchronizer.push(&symbol, kline),
+                Some(Err(e)) => {
+                    error!("while processing {} kline: {:?}", symbol, e);
+                    continue;
+                }
+                None => synchronizer.end(&symbol),
+            };
+
+            for bars in ready {
+                self.step(bars, apply);
+            }
+        }
+    }
+
+    fn step(&mut self, bars: Vec<Kline>, apply: bool) {
+        let symbols = bars.iter().map(|bar| bar.symbol.clone()).collect::<Vec<String>>();
+
+        for bar in bars {
+            self.universe.insert(bar);
+        }
+
+        if !apply {
+            return;
+        }
+
+        let mut signals = Vec::new();
+
+        for symbol in &symbols {
+            if let Some(history) = self.universe.history(symbol) {
+                for strategy in self.strategies.iter().filter(|strategy| strategy.is_ready(history)) {
+                    signals.extend(strategy.generate_signals(history));
+                }
+            }
+        }
+
+        for strategy in self
+            .portfolio_strategies
+            .iter()
+            .filter(|strategy| strategy.is_ready(&self.universe))
+        {
+            signals.extend(strategy.generate_signals(&self.universe));
+        }
+
+        for signal in signals {
+            for processor in &mut self.signal_processors {
+                processor.process_signal(&signal);
+            }
+        }
+    }
+}
+
+// Groups closed bars by open time and releases a group once every symbol whose
+// stream is still running has delivered a bar at or after that time. With a
+// patience, a symbol more than that far behind the newest bar stalls nobody:
+// the groups up to the patience before the newest bar are released without it.
+struct Synchronizer {
+    latest: HashMap<String, Option<DateTime<Utc>>>,
+    pending: BTreeMap<DateTime<Utc>, Vec<Kline>>,
+    patience: Option<TimeDelta>,
+    // oldest bar seen, the first one expected from symbols without any yet
+    first: Option<DateTime<Utc>>,
+    stalled: HashSet<String>,
+}
+
+impl Synchronizer {
+    fn new<'a>(symbols: impl Iterator<Item = &'a str>) -> Self {
+        Self {
+            latest: symbols.map(|symbol| (symbol.to_string(), None)).collect(),
+            pending: BTreeMap::new(),
+            patience: None,
+            first: None,
+            stalled: HashSet::new(),
+        }
+    }
+
+    fn with_patience(mut self, patience: Option<TimeDelta>) -> Self {
+        self.patience = patience;
+        self
+    }
+
+    fn push(&mut self, symbol: &str, mut kline: Kline) -> Vec<Vec<Kline>> {
+        if !kline.closed {
+            trace!("skipping unfinished {} bar at {}", symbol, kline.time);
+            return Vec::new();
+        }
+
+        kline.symbol = symbol.to_string();
+        self.first = Some(self.first.map_or(kline.time, |first| first.min(kline.time)));
+        if let Some(latest) = self.latest.get_mut(symbol) {
+            *latest = (*latest).max(Some(kline.time));
+        }
+
+        let bars = self.pending.entry(kline.time).or_default();
+        bars.retain(|bar| bar.symbol != kline.symbol);
+        bars.push(kline);
+
+        self.release()
+    }
+
+    fn end(&mut self, symbol: &str) -> Vec<Vec<Kline>> {
+        self.latest.remove(symbol);
+        self.release()
+    }
+
+    fn release(&mut self) -> Vec<Vec<Kline>> {
+        let newest = self.latest.values().flatten().max().copied();
+        let mut reached = Vec::new();
+
+        for (symbol, latest) in &self.latest {
+            let overdue = match (self.patience, newest) {
+                (Some(patience), Some(newest)) => {
+                    // without bars yet, the symbol is late once another one
+                    // got past the first bar
+                    let late = match latest {
+                        Some(latest) => newest - *latest > patience,
+                        None => self.first.is_some_and(|first| newest > first),
+                    };
+                    late.then_some(newest - patience)
+                }
+                _ => None,
+            };
+
+            match overdue {
+                Some(_) if self.stalled.insert(symbol.clone()) => {
+                    warn!("{} stopped sending bars, releasing the others without it", symbol)
+                }
+                None if self.stalled.remove(symbol) => info!("{} is sending bars again", symbol),
+                _ => {}
+            }
+
+            reached.push((*latest).max(overdue));
+        }
+
+        let until = match reached.into_iter().min() {
+            Some(Some(time)) => Some(time),
+            Some(None) => return Vec::new(),
+            None => None,
+        };
+
+        let ready = self
+            .pending
+            .keys()
+            .take_while(|time| until.is_none_or(|until| **time <= until))
+            .cloned()
+            .collect::<Vec<DateTime<Utc>>>();
+
+        ready
+            .into_iter()
+            .filter_map(|time| self.pending.remove(&time))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+    use crate::{
+        data_structures::{
+            kline::{helpers::generate_klines_with_interval, Kline},
+            signal::Signal,
+            universe::Universe,
+        },
+        indicators::{ema::EMAParams, price::PriceSource, IndicatorIdentifier},
+        processor::ProcessorMode,
+        signal_processors::helpers::Recorder,
+        source::replay::Replay,
+        strategies::{crossover::PriceCrossOverStrategy, PortfolioStrategy},
+    };
+
+    use super::{PortfolioProcessor, Synchronizer};
+
+    type Snapshot = (DateTime<Utc>, DateTime<Utc>, DateTime<Utc>);
+
+    // Records the time of the universe and the last bar of both symbols.
+    struct Observer {
+        snapshots: Arc<Mutex<Vec<Snapshot>>>,
+    }
+
+    impl PortfolioStrategy for Observer {
+        fn name(&self) -> &str {
+            "observer"
+        }
+
+        fn request_indicators(&self) -> Vec<(String, IndicatorIdentifier)> {
+            Vec::new()
+        }
+
+        fn generate_signals(&self, universe: &Universe) -> Vec<Signal> {
+            let last = |symbol| universe.history(symbol).unwrap().last(1)[0].time;
+
+            self.snapshots
+                .lock()
+                .unwrap()
+                .push((universe.time().unwrap(), last("BTCUSDT"), last("ETHUSDT")));
+            Vec::new()
+        }
+    }
+
+    fn source(symbol: &str, prices: &[f64], warmup: usize) -> Box<Replay> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let klines = generate_klines_with_interval(start, prices, 60);
+
+        Box::new(Replay::new(symbol.to_string(), "1m".to_string(), klines).with_warmup(warmup))
+    }
+
+    #[tokio::test]
+    async fn test_bars_are_synchronised_across_symbols() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        // ETH misses its fifth bar
+        let mut klines = generate_klines_with_interval(start, &[10.0; 10], 60);
+        klines.remove(4);
+        let eth = Box::new(Replay::new("ETHUSDT".to_string(), "1m".to_string(), klines).with_warmup(9));
+        let btc = source("BTCUSDT", &[100.0; 10], 10);
+
+        let snapshots = Arc::new(Mutex::new(Vec::new()));
+        let mut processor = PortfolioProcessor::new(vec![btc, eth], Vec::new())
+            .unwrap()
+            .with_portfolio_strategy(Box::new(Observer {
+                snapshots: snapshots.clone(),
+            }));
+        processor.start(ProcessorMode::Backtest).await.unwrap();
+
+        let snapshots = snapshots.lock().unwrap();
+        assert_eq!(snapshots.len(), 10);
+        assert!(snapshots.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(snapshots.iter().all(|(time, btc, eth)| btc == time && eth <= time));
+        assert_eq!(snapshots[4].2, snapshots[3].0);
+        assert_eq!(processor.universe().history("ETHUSDT").unwrap().len(), 9);
+    }
+
+    #[tokio::test]
+    async fn test_strategies_run_per_symbol_in_live_mode() {
+        let prices = (0..40).map(|i| 100.0 + (i % 7) as f64).collect::<Vec<f64>>();
+        let signals = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Box::new(Recorder {
+            signals: signals.clone(),
+        });
+        let strategy = PriceCrossOverStrategy::new(
+            "EMAPriceCrossOver".to_string(),
+            IndicatorIdentifier::EMA(EMAParams {
+                period: 20,
+                source: PriceSource::Close,
+            }),
+        );
+
+        let mut processor = PortfolioProcessor::new(
+            vec![source("BTCUSDT", &prices, 25), source("ETHUSDT", &prices, 25)],
+            vec![recorder],
+        )
+        .unwrap()
+        .with_strategy(Box::new(strategy));
+        processor.start(ProcessorMode::Live).await.unwrap();
+
+        let signals = signals.lock().unwrap();
+        let count = |symbol: &str| signals.iter().filter(|signal| signal.symbol == symbol).count();
+        assert_eq!(count("BTCUSDT"), 15);
+        assert_eq!(count("ETHUSDT"), 15);
+    }
+
+    #[test]
+    fn test_rejects_two_sources_for_one_symbol() {
+        let minutes = source("BTCUSDT", &[100.0; 10], 10);
+        let hours = Box::new(Replay::new("BTCUSDT".to_string(), "1h".to_string(), Vec::new()));
+
+        assert!(PortfolioProcessor::new(vec![minutes, hours], Vec::new()).is_err());
+    }
+
+    // Times of the released groups, with the symbols in each.
+    fn released(groups: Vec<Vec<Kline>>) -> Vec<(DateTime<Utc>, Vec<String>)> {
+        groups
+            .into_iter()
+            .map(|group| {
+                let mut symbols = group.iter().map(|bar| bar.symbol.clone()).collect::<Vec<String>>();
+                symbols.sort();
+                (group[0].time, symbols)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stalled_symbol_is_not_waited_for_with_patience() {
+        let klines = generate_klines_with_interval(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(), &[1.0; 5], 60);
+        let time = |index: usize| klines[index].time;
+        let both = || vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()];
+        let btc = || vec!["BTCUSDT".to_string()];
+
+        // without patience BTC waits for ETH however far ahead it gets
+        let mut synchronizer = Synchronizer::new(["BTCUSDT", "ETHUSDT"].into_iter());
+        assert!(synchronizer.push("BTCUSDT", klines[0].clone()).is_empty());
+        assert!(synchronizer.push("BTCUSDT", klines[1].clone()).is_empty());
+        assert!(synchronizer.push("BTCUSDT", klines[2].clone()).is_empty());
+
+        let mut synchronizer =
+            Synchronizer::new(["BTCUSDT", "ETHUSDT"].into_iter()).with_patience(Some(TimeDelta::minutes(1)));
+        // ETH sends nothing at first, its first bar is late once BTC's second one arrives
+        assert!(synchronizer.push("BTCUSDT", klines[0].clone()).is_empty());
+        assert_eq!(
+            released(synchronizer.push("BTCUSDT", klines[1].clone())),
+            [(time(0), btc())]
+        );
+
+        // a bar one interval late is still waited for
+        assert_eq!(
+            released(synchronizer.push("ETHUSDT", klines[1].clone())),
+            [(time(1), both())]
+        );
+        assert!(synchronizer.push("BTCUSDT", klines[2].clone()).is_empty());
+        assert_eq!(
+            released(synchronizer.push("BTCUSDT", klines[3].clone())),
+            [(time(2), btc())]
+        );
+        assert_eq!(
+            released(synchronizer.push("BTCUSDT", klines[4].clone())),
+            [(time(3), btc())]
+        );
+        assert_eq!(
+            released(synchronizer.push("ETHUSDT", klines[4].clone())),
+            [(time(4), both())]
+        );
+    }
+}
@@ -6,13 +6,14 @@ use crate::data_structures::signal::Signal;
 
 use super::SignalProcessor;
 
+#[derive(Default)]
 pub struct Logging {
     last_signal: Option<Signal>,
 }
 
 impl Logging {
     pub fn new() -> Self {
-        Self { last_signal: None }
+        Self::default()
     }
 }
 
@@ -11,3 +11,32 @@ pub trait SignalProcessor {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
+
+#[cfg(test)]
+pub(crate) mod helpers {
+    use std::{
+        any::Any,
+        sync::{Arc, Mutex},
+    };
+
+    use super::SignalProcessor;
+    use crate::data_structures::signal::Signal;
+
+    pub(crate) struct Recorder {
+        pub(crate) signals: Arc<Mutex<Vec<Signal>>>,
+    }
+
+    impl SignalProcessor for Recorder {
+        fn process_signal(&mut self, signal: &Signal) {
+            self.signals.lock().unwrap().push(signal.clone());
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+}
@@ -0,0 +1,616 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::Stream;
+use log::{info, trace, warn};
+
+use crate::data_structures::{interval::Interval, kline::Kline};
+
+use super::{Result, Source};
+
+// Each record is the open time in milliseconds followed by open, high, low,
+// close and volume, all little endian.
+const MAGIC: &[u8; 4] = b"CRNK";
+const VERSION: u8 = 1;
+const HEADER_SIZE: usize = 5;
+const RECORD_SIZE: usize = 48;
+
+// Next to the klines, an index of the ranges the source had no bars for, such
+// as exchange maintenance windows, as pairs of start and end milliseconds.
+const GAPS_MAGIC: &[u8; 4] = b"CRNG";
+const GAP_SIZE: usize = 16;
+
+type Range = (DateTime<Utc>, DateTime<Utc>);
+
+// Keeps the closed klines fetched by another source on disk, one file per
+// exchange, symbol and interval, and only asks the source for the bars that
+// are not cached yet.
+pub struct Cache {
+    source: Box<dyn Source>,
+    directory: PathBuf,
+    range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    offline: bool,
+}
+
+impl Cache {
+    pub fn new(source: Box<dyn Source>, directory: PathBuf) -> Self {
+        Self {
+            source,
+            directory,
+            range: None,
+            offline: false,
+        }
+    }
+
+    pub fn with_range(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.range = Some((start, end));
+        self
+    }
+
+    // Serves history from the cache alone, without touching the source.
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    fn path(&self) -> PathBuf {
+        // `1m` and `1M` would collide on case-insensitive file systems
+        let interval = self.source.timeframe().replace('M', "mo");
+
+        self.directory
+            .join(self.source.name())
+            .join(format!("{}_{}.klines", self.source.symbol(), interval))
+    }
+
+    fn gaps_path(&self) -> PathBuf {
+        self.path().with_extension("gaps")
+    }
+
+    fn interval(&self) -> Option<Interval> {
+        self.source.timeframe().parse().ok()
+    }
+
+    async fn load(path: &Path) -> Result<Vec<Kline>> {
+        let bytes = match tokio::fs::read(path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Box::new(e)),
+        };
+
+        Self::decode(&bytes).ok_or_else(|| format!("corrupted kline cache: {}", path.display()).into())
+    }
+
+    async fn store(path: &Path, klines: &BTreeMap<DateTime<Utc>, Kline>) -> Result<()> {
+        if let Some(directory) = path.parent() {
+            tokio::fs::create_dir_all(directory).await?;
+        }
+
+        let temporary = path.with_extension("tmp");
+        tokio::fs::write(&temporary, Self::encode(klines.values())).await?;
+        tokio::fs::rename(&temporary, path).await?;
+
+        Ok(())
+    }
+
+    async fn load_gaps(path: &Path) -> Result<Vec<Range>> {
+        let bytes = match tokio::fs::read(path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Box::new(e)),
+        };
+
+        Self::decode_gaps(&bytes).ok_or_else(|| format!("corrupted gap index: {}", path.display()).into())
+    }
+
+    async fn store_gaps(path: &Path, gaps: &[Range]) -> Result<()> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(GAPS_MAGIC);
+        bytes.push(VERSION);
+
+        for (start, end) in gaps {
+            bytes.extend_from_slice(&start.timestamp_millis().to_le_bytes());
+            bytes.extend_from_slice(&end.timestamp_millis().to_le_bytes());
+        }
+
+        let temporary = path.with_extension("gaps.tmp");
+        tokio::fs::write(&temporary, bytes).await?;
+        tokio::fs::rename(&temporary, path).await?;
+
+        Ok(())
+    }
+
+    fn decode_gaps(bytes: &[u8]) -> Option<Vec<Range>> {
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != GAPS_MAGIC || bytes[4] != VERSION {
+            return None;
+        }
+
+        let records = &bytes[HEADER_SIZE..];
+        if !records.len().is_multiple_of(GAP_SIZE) {
+            return None;
+        }
+
+        records
+            .chunks_exact(GAP_SIZE)
+            .map(|record| {
+                let time = |i: usize| {
+                    let millis = i64::from_le_bytes(record[i * 8..(i + 1) * 8].try_into().ok()?);
+                    DateTime::from_timestamp_millis(millis)
+                };
+
+                Some((time(0)?, time(1)?))
+            })
+            .collect()
+    }
+
+    fn encode<'a>(klines: impl Iterator<Item = &'a Kline>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+
+        for kline in klines {
+            bytes.extend_from_slice(&kline.time.timestamp_millis().to_le_bytes());
+            for value in [kline.open, kline.high, kline.low, kline.close, kline.volume] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Vec<Kline>> {
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != MAGIC || bytes[4] != VERSION {
+            return None;
+        }
+
+        let records = &bytes[HEADER_SIZE..];
+        if !records.len().is_multiple_of(RECORD_SIZE) {
+            return None;
+        }
+
+        records
+            .chunks_exact(RECORD_SIZE)
+            .map(|record| {
+                let field = |i: usize| record[i * 8..(i + 1) * 8].try_into().ok();
+                let value = |i: usize| field(i).map(f64::from_le_bytes);
+
+                Some(Kline {
+                    time: DateTime::from_timestamp_millis(i64::from_le_bytes(field(0)?))?,
+                    open: value(1)?,
+                    high: value(2)?,
+                    low: value(3)?,
+                    close: value(4)?,
+                    volume: value(5)?,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    // Ranges of `[start, end]` not covered by the cached klines: before the
+    // first bar, after the last one and any hole in between.
+    fn missing_ranges(
+        cached: &BTreeMap<DateTime<Utc>, Kline>,
+        interval: Option<Interval>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<Range> {
+        let step = interval.map_or(TimeDelta::zero(), |interval| interval.duration());
+        let next = |time: DateTime<Utc>| interval.map_or(time, |interval| interval.next(time));
+        let before = |time: DateTime<Utc>| time - TimeDelta::milliseconds(1);
+
+        let (first, last) = match (cached.range(start..=end).next(), cached.range(start..=end).next_back()) {
+            (Some((first, _)), Some((last, _))) => (*first, *last),
+            _ => return vec![(start, end)],
+        };
+
+        let mut ranges = Vec::new();
+
+        if first - start >= step && first > start {
+            ranges.push((start, before(first)));
+        }
+
+        if interval.is_some() {
+            let times = cached.range(first..=last).map(|(time, _)| *time).collect::<Vec<_>>();
+            for window in times.windows(2) {
+                if next(window[0]) < window[1] {
+                    ranges.push((next(window[0]), before(window[1])));
+                }
+            }
+        }
+
+        if end - last >= step && end > last {
+            ranges.push((next(last), end));
+        }
+
+        ranges
+    }
+
+    fn validate_continuity(&self, klines: &[Kline]) {
+        let Some(interval) = self.interval() else {
+            return;
+        };
+
+        for window in klines.windows(2) {
+            if interval.next(window[0].time) != window[1].time {
+                warn!(
+                    "{} {} {} history is not continuous between {} and {}",
+                    self.source.name(),
+                    self.source.symbol(),
+                    interval,
+                    window[0].time,
+                    window[1].time
+                );
+            }
+        }
+    }
+
+    async fn fetch(&self) -> Result<Vec<Kline>> {
+        let path = self.path();
+        let mut klines = match Self::load(&path).await {
+            Ok(klines) => BTreeMap::from_iter(klines.into_iter().map(|kline| (kline.time, kline))),
+            Err(e) => {
+                warn!("{}, discarding it", e);
+                BTreeMap::new()
+            }
+        };
+        let cached = klines.len();
+        let gaps_path = self.gaps_path();
+        let mut gaps = Self::load_gaps(&gaps_path).await.unwrap_or_else(|e| {
+            warn!("{}, discarding it", e);
+            Vec::new()
+        });
+        let known_gaps = gaps.len();
+
+        let mut fetched = Vec::new();
+        let mut requested = Vec::new();
+        if !self.offline {
+            match self.range {
+                Some((start, end)) => {
+                    for (from, to) in Self::missing_ranges(&klines, self.interval(), start, end) {
+                        if gaps.iter().any(|gap| gap.0 <= from && to <= gap.1) {
+                            continue;
+                        }
+
+                        trace!("fetching missing klines from {} to {}", from, to);
+                        fetched.extend(self.source.fetch_history_range(from, to).await?);
+                        requested.push((from, to));
+                    }
+                }
+                // without a range, only the bars after the last cached one
+                None => match klines.keys().next_back() {
+                    Some(last) => {
+                        let from = self.interval().map_or(*last, |interval| interval.next(*last));
+
+                        trace!("fetching klines from {}", from);
+                        fetched.extend(self.source.fetch_history_range(from, Utc::now()).await?);
+                    }
+                    None => fetched.extend(self.source.fetch_history().await?),
+                },
+            }
+        }
+
+        let mut unfinished = None;
+        for kline in fetched {
+            match kline.closed {
+                true => {
+                    klines.insert(kline.time, kline);
+                }
+                false => unfinished = Some(kline),
+            }
+        }
+
+        if klines.len() != cached {
+            info!("cached {} new klines in {}", klines.len() - cached, path.display());
+            Self::store(&path, &klines).await?;
+        }
+
+        // Requested ranges still missing before the last cached bar have no
+        // bars on the exchange. Those after it may just not have closed yet.
+        if let (Some((start, end)), Some(last)) = (self.range, klines.keys().next_back()) {
+            for (from, to) in Self::missing_ranges(&klines, self.interval(), start, end) {
+                if to < *last && requested.iter().any(|range| range.0 <= from && to <= range.1) {
+                    gaps.push((from, to));
+                }
+            }
+        }
+
+        if gaps.len() != known_gaps {
+            info!(
+                "recorded {} empty ranges in {}",
+                gaps.len() - known_gaps,
+                gaps_path.display()
+            );
+            Self::store_gaps(&gaps_path, &gaps).await?;
+        }
+
+        let mut history: Vec<Kline> = match self.range {
+            Some((start, end)) => klines.range(start..=end).map(|(_, kline)| kline.clone()).collect(),
+            None => klines.into_values().collect(),
+        };
+
+        self.validate_continuity(&history);
+
+        for kline in history.iter_mut() {
+            kline.symbol = self.source.symbol().to_string();
+        }
+
+        if let Some(kline) = unfinished {
+            if history.last().is_none_or(|last| last.time < kline.time) {
+                history.push(kline);
+            }
+        }
+
+        Ok(history)
+    }
+}
+
+impl Source for Cache {
+    fn name(&self) -> &str {
+        self.source.name()
+    }
+
+    fn symbol(&self) -> &str {
+        self.source.symbol()
+    }
+
+    fn timeframe(&self) -> &str {
+        self.source.timeframe()
+    }
+
+    fn fetch_history(&self) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
+        Box::pin(self.fetch())
+    }
+
+    fn fetch_live(&self) -> Pin<Box<dyn Stream<Item = Result<Kline>> + Send>> {
+        self.source.fetch_live()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        path::{Path, PathBuf},
+        pin::Pin,
+        sync::{Arc, Mutex},
+    };
+
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+    use futures::{stream, Stream};
+
+    use crate::{
+        data_structures::{interval::Interval, kline::helpers::generate_klines_with_interval, kline::Kline},
+        source::{Result, Source},
+    };
+
+    use super::Cache;
+
+    type Requests = Arc<Mutex<Vec<(DateTime<Utc>, DateTime<Utc>)>>>;
+
+    // Serves one minute klines for any requested range, except during the
+    // maintenance window, and records each request.
+    struct Exchange {
+        requests: Requests,
+        maintenance: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    }
+
+    impl Source for Exchange {
+        fn name(&self) -> &str {
+            "exchange"
+        }
+
+        fn symbol(&self) -> &str {
+            "BTCUSDT"
+        }
+
+        fn timeframe(&self) -> &str {
+            "1m"
+        }
+
+        fn fetch_history(&self) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
+            Box::pin(async { Err("not supported".into()) })
+        }
+
+        fn fetch_history_range(
+            &self,
+            start: DateTime<Utc>,
+            end: DateTime<Utc>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
+            self.requests.lock().unwrap().push((start, end));
+            let count = ((end - start).num_minutes() + 1) as usize;
+            let prices = (0..count).map(|i| i as f64).collect::<Vec<f64>>();
+            let klines = generate_klines_with_interval(start, &prices, 60)
+                .into_iter()
+                .filter(|kline| {
+                    self.maintenance
+                        .is_none_or(|(from, to)| kline.time < from || kline.time > to)
+                })
+                .collect();
+
+            Box::pin(async move { Ok(klines) })
+        }
+
+        fn fetch_live(&self) -> Pin<Box<dyn Stream<Item = Result<Kline>> + Send>> {
+            Box::pin(stream::empty())
+        }
+    }
+
+    fn directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("cerunnos-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        directory
+    }
+
+    fn cache(directory: &Path, requests: &Requests) -> Cache {
+        let source = Exchange {
+            requests: requests.clone(),
+            maintenance: None,
+        };
+
+        Cache::new(Box::new(source), directory.to_path_buf())
+    }
+
+    fn time(minute: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + TimeDelta::minutes(minute)
+    }
+
+    #[tokio::test]
+    async fn test_warm_cache_does_not_refetch() {
+        let directory = directory("warm");
+        let requests = Requests::default();
+
+        let first = cache(&directory, &requests)
+            .with_range(time(0), time(59))
+            .fetch_history()
+            .await
+            .unwrap();
+        let second = cache(&directory, &requests)
+            .with_range(time(0), time(59))
+            .fetch_history()
+            .await
+            .unwrap();
+
+        assert_eq!(first.len(), 60);
+        assert_eq!(first, second);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert!(directory.join("exchange").join("BTCUSDT_1m.klines").exists());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_only_missing_ranges_are_fetched() {
+        let directory = directory("missing");
+        let requests = Requests::default();
+
+        cache(&directory, &requests)
+            .with_range(time(10), time(19))
+            .fetch_history()
+            .await
+            .unwrap();
+        requests.lock().unwrap().clear();
+
+        let klines = cache(&directory, &requests)
+            .with_range(time(0), time(29))
+            .fetch_history()
+            .await
+            .unwrap();
+
+        assert_eq!(klines.len(), 30);
+        assert!(klines
+            .windows(2)
+            .all(|w| w[1].time - w[0].time == TimeDelta::minutes(1)));
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![(time(0), time(10) - TimeDelta::milliseconds(1)), (time(20), time(29))]
+        );
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_empty_ranges_are_not_refetched() {
+        let directory = directory("maintenance");
+        let requests = Requests::default();
+        let cache = || {
+            let source = Exchange {
+                requests: requests.clone(),
+                maintenance: Some((time(10), time(14))),
+            };
+            Cache::new(Box::new(source), directory.clone()).with_range(time(0), time(29))
+        };
+
+        let first = cache().fetch_history().await.unwrap();
+        let second = cache().fetch_history().await.unwrap();
+
+        assert_eq!(first.len(), 25);
+        assert_eq!(first, second);
+        assert_eq!(*requests.lock().unwrap(), vec![(time(0), time(29))]);
+        assert!(directory.join("exchange").join("BTCUSDT_1m.gaps").exists());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_without_range_only_newer_klines_are_fetched() {
+        let directory = directory("newer");
+        let requests = Requests::default();
+        let start = Interval::Minutes(1).bucket_start(Utc::now()) - TimeDelta::minutes(30);
+
+        cache(&directory, &requests)
+            .with_range(start, start + TimeDelta::minutes(9))
+            .fetch_history()
+            .await
+            .unwrap();
+        let klines = cache(&directory, &requests).fetch_history().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].0, start + TimeDelta::minutes(10));
+        assert_eq!(klines[0].time, start);
+        assert!(klines.len() > 30);
+        assert!(klines
+            .windows(2)
+            .all(|w| w[1].time - w[0].time == TimeDelta::minutes(1)));
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_offline_serves_cached_klines_only() {
+        let directory = directory("offline");
+        let requests = Requests::default();
+
+        let cold = cache(&directory, &requests)
+            .offline()
+            .with_range(time(0), time(9))
+            .fetch_history()
+            .await
+            .unwrap();
+        assert!(cold.is_empty());
+
+        cache(&directory, &requests)
+            .with_range(time(0), time(9))
+            .fetch_history()
+            .await
+            .unwrap();
+        requests.lock().unwrap().clear();
+
+        let warm = cache(&directory, &requests)
+            .offline()
+            .with_range(time(0), time(19))
+            .fetch_history()
+            .await
+            .unwrap();
+
+        assert_eq!(warm.len(), 10);
+        assert!(warm.iter().all(|kline| kline.symbol == "BTCUSDT"));
+        assert!(requests.lock().unwrap().is_empty());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_cache_is_refetched() {
+        let directory = directory("corrupted");
+        let requests = Requests::default();
+        std::fs::create_dir_all(directory.join("exchange")).unwrap();
+        std::fs::write(directory.join("exchange").join("BTCUSDT_1m.klines"), b"garbage").unwrap();
+
+        let klines = cache(&directory, &requests)
+            .with_range(time(0), time(9))
+            .fetch_history()
+            .await
+            .unwrap();
+
+        assert_eq!(klines.len(), 10);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,15 +1,76 @@
+pub mod cache;
+pub mod replay;
+pub mod resample;
+pub mod synthetic;
+
 use std::{error::Error, future::Future, pin::Pin};
 
+use chrono::{DateTime, Utc};
 use futures::Stream;
 
 use crate::data_structures::kline::Kline;
 pub type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync + 'static>>;
 
-pub trait Source {
+pub trait Source: Send + Sync {
     fn name(&self) -> &str;
     fn symbol(&self) -> &str;
     fn timeframe(&self) -> &str;
 
     fn fetch_history(&self) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>>;
+
+    fn fetch_history_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
+        let history = self.fetch_history();
+
+        Box::pin(async move {
+            let klines = history.await?;
+
+            Ok(klines
+                .into_iter()
+                .filter(|kline| kline.time >= start && kline.time <= end)
+                .collect())
+        })
+    }
+
     fn fetch_live(&self) -> Pin<Box<dyn Stream<Item = Result<Kline>> + Send>>;
 }
+
+#[cfg(test)]
+pub(crate) mod helpers {
+    use std::{future::Future, pin::Pin};
+
+    use futures::{stream, Stream};
+
+    use super::{Result, Source};
+    use crate::data_structures::kline::Kline;
+
+    pub(crate) struct StaticSource {
+        pub(crate) history: Vec<Kline>,
+        pub(crate) live: Vec<Kline>,
+    }
+
+    impl Source for StaticSource {
+        fn name(&self) -> &str {
+            "static"
+        }
+
+        fn symbol(&self) -> &str {
+            "TEST"
+        }
+
+        fn timeframe(&self) -> &str {
+            "1m"
+        }
+
+        fn fetch_history(&self) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
+            Box::pin(async move { Ok(self.history.clone()) })
+        }
+
+        fn fetch_live(&self) -> Pin<Box<dyn Stream<Item = Result<Kline>> + Send>> {
+            Box::pin(stream::iter(self.live.clone().into_iter().map(Ok)))
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+use futures::{stream, Stream, StreamExt};
+use log::warn;
+
+use crate::data_structures::{interval::Interval, kline::Kline};
+
+use super::{Result, Source};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Speed {
+    RealTime,
+    Accelerated(f64),
+    Unthrottled,
+}
+
+// Plays stored klines back through `fetch_live` so the live code path can run
+// without an exchange. The first `warmup` bars are served as history.
+pub struct Replay {
+    name: String,
+    symbol: String,
+    timeframe: String,
+    klines: Arc<Vec<Kline>>,
+    warmup: usize,
+    speed: Speed,
+    ticks: usize,
+}
+
+impl Replay {
+    pub fn new(symbol: String, timeframe: String, klines: Vec<Kline>) -> Self {
+        Self {
+            name: "replay".to_string(),
+            symbol,
+            timeframe,
+            klines: Arc::new(klines),
+            warmup: 0,
+            speed: Speed::Unthrottled,
+            ticks: 1,
+        }
+    }
+
+    pub async fn from_source(source: &dyn Source) -> Result<Self> {
+        let klines = source.fetch_history().await?;
+
+        Ok(Self::new(
+            source.symbol().to_string(),
+            source.timeframe().to_string(),
+            klines,
+        ))
+    }
+
+    pub fn with_warmup(mut self, bars: usize) -> Self {
+        self.warmup = bars.min(self.klines.len());
+        self
+    }
+
+    // Accelerated speeds have to be positive and finite.
+    pub fn with_speed(mut self, speed: Speed) -> Result<Self> {
+        if let Speed::Accelerated(factor) = speed {
+            if !(factor.is_finite() && factor > 0.0) {
+                return Err(format!("invalid replay speed: {}", factor).into());
+            }
+        }
+
+        self.speed = speed;
+        Ok(self)
+    }
+
+    // Splits every replayed bar into `ticks` partial updates, the last one
+    // being the closed bar itself.
+    pub fn with_ticks(mut self, ticks: usize) -> Self {
+        self.ticks = ticks.max(1);
+        self
+    }
+
+    fn tick_delay(&self) -> Option<Duration> {
+        let speed = match self.speed {
+            Speed::RealTime => 1.0,
+            Speed::Accelerated(speed) => speed,
+            Speed::Unthrottled => return None,
+        };
+
+        match self.timeframe.parse::<Interval>() {
+            Ok(interval) => interval.duration().to_std().ok().and_then(|duration| {
+                Duration::try_from_secs_f64(duration.as_secs_f64() / (speed * self.ticks as f64)).ok()
+            }),
+            Err(e) => {
+                warn!("{}, replaying without delay", e);
+                None
+            }
+        }
+    }
+
+    // Walks open -> low -> high -> close for rising bars and open -> high ->
+    // low -> close for falling ones, sampling `count` points along the way.
+    fn split(kline: &Kline, count: usize) -> Vec<Kline> {
+        if count <= 1 {
+            return vec![kline.clone()];
+        }
+
+        let path = match kline.close >= kline.open {
+            true => [kline.open, kline.low, kline.high, kline.close],
+            false => [kline.open, kline.high, kline.low, kline.close],
+        };
+        let legs = path.windows(2).map(|leg| (leg[1] - leg[0]).abs()).collect::<Vec<f64>>();
+        let length = legs.iter().sum::<f64>();
+
+        let mut high = kline.open;
+        let mut low = kline.open;
+        let mut ticks = Vec::with_capacity(count);
+
+        for i in 1..count {
+            let fraction = i as f64 / count as f64;
+            let mut distance = fraction * length;
+            let mut price = kline.open;
+
+            for (leg, size) in legs.iter().enumerate() {
+                if distance <= *size || leg == legs.len() - 1 {
+                    let direction = (path[leg + 1] - path[leg]).signum();
+                    price = path[leg] + direction * distance.min(*size);
+                    break;
+                }
+                distance -= size;
+            }
+
+            high = high.max(price);
+            low = low.min(price);
+
+            ticks.push(Kline {
+                close: price,
+                high,
+                low,
+                volume: kline.volume * fraction,
+                closed: false,
+                ..kline.clone()
+            });
+        }
+
+        ticks.push(kline.clone());
+        ticks
+    }
+}
+
+impl Source for Replay {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn timeframe(&self) -> &str {
+        &self.timeframe
+    }
+
+    fn fetch_history(&self) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
+        Box::pin(async move { Ok(self.klines[..self.warmup].to_vec()) })
+    }
+
+    fn fetch_live(&self) -> Pin<Box<dyn Stream<Item = Result<Kline>> + Send>> {
+        let klines = self.klines.clone();
+        let ticks = self.ticks;
+        let delay = self.tick_delay();
+        let replayed = (self.warmup..klines.len()).flat_map(move |i| Self::split(&klines[i], ticks));
+
+        Box::pin(stream::iter(replayed).enumerate().then(move |(i, kline)| async move {
+            if let (Some(delay), true) = (delay, i > 0) {
+                tokio::time::sleep(delay).await;
+            }
+
+            Ok(kline)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Instant,
+    };
+
+    use chrono::Utc;
+    use futures::StreamExt;
+
+    use crate::{
+        data_structures::kline::{helpers::generate_klines_with_interval, Kline},
+        processor::{Processor, ProcessorMode},
+        signal_processors::helpers::Recorder,
+        source::Source,
+    };
+
+    use super::{Replay, Speed};
+
+    fn klines(count: usize) -> Vec<Kline> {
+        let prices = (0..count).map(|i| 100.0 + (i % 7) as f64).collect::<Vec<f64>>();
+        generate_klines_with_interval(Utc::now(), &prices, 1)
+    }
+
+    #[tokio::test]
+    async fn test_warmup_is_served_as_history() {
+        let klines = klines(10);
+        let replay = Replay::new("TEST".to_string(), "1s".to_string(), klines.clone()).with_warmup(4);
+
+        let history = replay.fetch_history().await.unwrap();
+        let live = replay.fetch_live().map(|k| k.unwrap()).collect::<Vec<_>>().await;
+
+        assert_eq!(history.len(), 4);
+        assert_eq!(live.len(), 6);
+        assert_eq!(live[0], klines[4]);
+    }
+
+    #[tokio::test]
+    async fn test_accelerated_replay_is_paced() {
+        let replay = Replay::new("TEST".to_string(), "1s".to_string(), klines(5))
+            .with_speed(Speed::Accelerated(50.0))
+            .unwrap();
+
+        let start = Instant::now();
+        let live = replay.fetch_live().collect::<Vec<_>>().await;
+
+        assert_eq!(live.len(), 5);
+        assert!(start.elapsed().as_millis() >= 80);
+    }
+
+    #[test]
+    fn test_rejects_invalid_speeds() {
+        for speed in [0.0, -2.0, f64::NAN, f64::INFINITY] {
+            let replay = Replay::new("TEST".to_string(), "1s".to_string(), klines(5));
+
+            assert!(replay.with_speed(Speed::Accelerated(speed)).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bars_are_split_into_ticks() {
+        let kline = Kline {
+            open: 100.0,
+            high: 110.0,
+            low: 95.0,
+            close: 105.0,
+            volume: 40.0,
+            ..Default::default()
+        };
+        let replay = Replay::new("TEST".to_string(), "1m".to_string(), vec![kline.clone()]).with_ticks(4);
+
+        let ticks = replay.fetch_live().map(|k| k.unwrap()).collect::<Vec<_>>().await;
+
+        assert_eq!(ticks.len(), 4);
+        assert!(ticks[..3].iter().all(|tick| !tick.closed && tick.time == kline.time));
+        assert_eq!(ticks[3], kline);
+        assert!(ticks.windows(2).all(|w| w[0].high <= w[1].high && w[0].low >= w[1].low));
+        assert!(ticks.iter().all(|tick| tick.low >= 95.0 && tick.high <= 110.0));
+        assert_eq!(ticks[1].volume, 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_drives_processor_live_mode() {
+        let replay = Replay::new("TEST".to_string(), "1s".to_string(), klines(40))
+            .with_warmup(25)
+            .with_ticks(3);
+        let signals = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Box::new(Recorder {
+            signals: signals.clone(),
+        });
+
+        let mut processor = Processor::new(Box::new(replay), vec![recorder]);
+        processor.start(ProcessorMode::Live).await.unwrap();
+
+        assert_eq!(signals.lock().unwrap().len(), 15);
+    }
+}
@@ -0,0 +1,108 @@
+use std::{future::Future, pin::Pin};
+
+use futures::Stream;
+
+use crate::data_structures::{
+    interval::Interval,
+    kline::Kline,
+    resampler::{resample, resample_stream},
+};
+
+use super::{Result, Source};
+
+// Serves another source's klines aggregated into a higher timeframe, both for
+// history and for the live stream.
+pub struct Resample {
+    source: Box<dyn Source>,
+    from: Interval,
+    to: Interval,
+    timeframe: String,
+}
+
+impl Resample {
+    pub fn new(source: Box<dyn Source>, to: Interval) -> Result<Self> {
+        let from: Interval = source.timeframe().parse()?;
+
+        if to.duration() < from.duration() {
+            return Err(format!("cannot resample {} klines into {}", from, to).into());
+        }
+
+        Ok(Self {
+            source,
+            from,
+            to,
+            timeframe: to.to_string(),
+        })
+    }
+}
+
+impl Source for Resample {
+    fn name(&self) -> &str {
+        self.source.name()
+    }
+
+    fn symbol(&self) -> &str {
+        self.source.symbol()
+    }
+
+    fn timeframe(&self) -> &str {
+        &self.timeframe
+    }
+
+    fn fetch_history(&self) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
+        Box::pin(async move {
+            let klines = self.source.fetch_history().await?;
+
+            Ok(resample(&klines, self.from, self.to))
+        })
+    }
+
+    fn fetch_live(&self) -> Pin<Box<dyn Stream<Item = Result<Kline>> + Send>> {
+        Box::pin(resample_stream(self.source.fetch_live(), self.from, self.to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use futures::StreamExt;
+
+    use crate::{
+        data_structures::{interval::Interval, kline::helpers::generate_klines_with_interval},
+        source::{helpers::StaticSource, Source},
+    };
+
+    use super::Resample;
+
+    #[tokio::test]
+    async fn test_history_and_live_are_resampled() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let prices = (0..20).map(|i| 100.0 + i as f64).collect::<Vec<f64>>();
+        let klines = generate_klines_with_interval(start, &prices, 60);
+        let source = StaticSource {
+            history: klines[..15].to_vec(),
+            live: klines[15..].to_vec(),
+        };
+
+        let resample = Resample::new(Box::new(source), Interval::Minutes(5)).unwrap();
+        let history = resample.fetch_history().await.unwrap();
+        let live = resample.fetch_live().map(|k| k.unwrap()).collect::<Vec<_>>().await;
+
+        assert_eq!(resample.timeframe(), "5m");
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[2].close, 114.0);
+        assert_eq!(live.len(), 5);
+        assert!(live.last().unwrap().closed);
+        assert_eq!(live.last().unwrap().close, 119.0);
+    }
+
+    #[test]
+    fn test_cannot_resample_into_lower_timeframe() {
+        let source = StaticSource {
+            history: Vec::new(),
+            live: Vec::new(),
+        };
+
+        assert!(Resample::new(Box::new(source), Interval::Seconds(30)).is_err());
+    }
+}
@@ -0,0 +1,466 @@
+use std::{f64::consts::PI, future::Future, pin::Pin};
+
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::data_structures::{interval::Interval, kline::Kline};
+
+use super::{Result, Source};
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+const STEPS_PER_BAR: usize = 16;
+const MIN_PRICE: f64 = 1e-8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regime {
+    pub drift: f64,
+    pub volatility: f64,
+}
+
+// Drifts and volatilities are annualised. Ornstein-Uhlenbeck works on the
+// price level, so its mean and volatility are expressed in price units.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Model {
+    GeometricBrownianMotion {
+        drift: f64,
+        volatility: f64,
+    },
+    OrnsteinUhlenbeck {
+        mean: f64,
+        reversion: f64,
+        volatility: f64,
+    },
+    JumpDiffusion {
+        drift: f64,
+        volatility: f64,
+        jump_intensity: f64,
+        jump_mean: f64,
+        jump_volatility: f64,
+    },
+    // Geometric Brownian motion whose parameters change when the market
+    // switches regime, which happens with the given probability per bar.
+    RegimeSwitching {
+        regimes: Vec<Regime>,
+        switch_probability: f64,
+    },
+}
+
+pub struct Synthetic {
+    name: String,
+    symbol: String,
+    timeframe: String,
+    model: Model,
+    seed: u64,
+    start: DateTime<Utc>,
+    start_price: f64,
+    base_volume: f64,
+    history_bars: usize,
+    live_bars: usize,
+}
+
+struct Generator<'a> {
+    model: &'a Model,
+    rng: StdRng,
+    dt: f64,
+    price: f64,
+    regime: usize,
+    base_volume: f64,
+}
+
+impl<'a> Generator<'a> {
+    fn normal(&mut self) -> f64 {
+        // Box-Muller transform
+        let u1: f64 = 1.0 - self.rng.random::<f64>();
+        let u2: f64 = self.rng.random();
+
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+
+    fn poisson(&mut self, lambda: f64) -> usize {
+        let limit = (-lambda).exp();
+        let mut count = 0;
+        let mut product: f64 = self.rng.random();
+
+        while product > limit {
+            count += 1;
+            product *= self.rng.random::<f64>();
+        }
+
+        count
+    }
+
+    fn gbm_step(&mut self, drift: f64, volatility: f64) -> f64 {
+        let z = self.normal();
+        self.price * ((drift - 0.5 * volatility.powi(2)) * self.dt + volatility * self.dt.sqrt() * z).exp()
+    }
+
+    fn step(&mut self) -> f64 {
+        let dt = self.dt;
+
+        let price = match self.model {
+            Model::GeometricBrownianMotion { drift, volatility } => self.gbm_step(*drift, *volatility),
+            Model::OrnsteinUhlenbeck {
+                mean,
+                reversion,
+                volatility,
+            } => {
+                let decay = (-reversion * dt).exp();
+                // without reversion the process is a plain random walk
+                let deviation = match (reversion * dt).abs() < 1e-12 {
+                    true => volatility * dt.sqrt(),
+                    false => volatility * ((1.0 - decay.powi(2)) / (2.0 * reversion)).sqrt(),
+                };
+                mean + (self.price - mean) * decay + deviation * self.normal()
+            }
+            Model::JumpDiffusion {
+                drift,
+                volatility,
+                jump_intensity,
+                jump_mean,
+                jump_volatility,
+            } => {
+                // compensate the drift so jumps do not bias the expected return
+                let compensation = jump_intensity * ((jump_mean + 0.5 * jump_volatility.powi(2)).exp() - 1.0);
+                let price = self.gbm_step(drift - compensation, *volatility);
+                let jumps = self.poisson(jump_intensity * dt);
+                let size = (0..jumps)
+                    .map(|_| jump_mean + jump_volatility * self.normal())
+                    .sum::<f64>();
+                price * size.exp()
+            }
+            Model::RegimeSwitching { regimes, .. } => {
+                let Regime { drift, volatility } = regimes[self.regime].clone();
+                self.gbm_step(drift, volatility)
+            }
+        };
+
+        price.max(MIN_PRICE)
+    }
+
+    fn switch_regime(&mut self) {
+        if let Model::RegimeSwitching {
+            regimes,
+            switch_probability,
+        } = self.model
+        {
+            if regimes.len() > 1 && self.rng.random_bool(switch_probability.clamp(0.0, 1.0)) {
+                let next = self.rng.random_range(0..regimes.len() - 1);
+                self.regime = if next >= self.regime { next + 1 } else { next };
+            }
+        }
+    }
+
+    fn bar(&mut self, time: DateTime<Utc>, symbol: &str) -> Kline {
+        self.switch_regime();
+
+        let open = self.price;
+        let (mut high, mut low) = (open, open);
+        let mut path = 0.0;
+
+        for _ in 0..STEPS_PER_BAR {
+            let previous = self.price;
+            self.price = self.step();
+            high = high.max(self.price);
+            low = low.min(self.price);
+            path += (self.price / previous).ln().abs();
+        }
+
+        // volume grows with the distance travelled inside the bar
+        let noise = (0.25 * self.normal()).exp();
+        let volume = self.base_volume * (1.0 + 100.0 * path) * noise;
+
+        Kline {
+            time,
+            symbol: symbol.to_string(),
+            open,
+            close: self.price,
+            high,
+            low,
+            volume,
+            closed: true,
+        }
+    }
+}
+
+impl Synthetic {
+    pub fn new(symbol: String, timeframe: String, model: Model, seed: u64) -> Result<Self> {
+        if let Model::RegimeSwitching { regimes, .. } = &model {
+            if regimes.is_empty() {
+                return Err("regime switching needs at least one regime".into());
+            }
+        }
+
+        Ok(Self {
+            name: "synthetic".to_string(),
+            symbol,
+            timeframe,
+            model,
+            seed,
+            start: DateTime::from_timestamp(1_704_067_200, 0).expect("valid start time"),
+            start_price: 100.0,
+            base_volume: 1000.0,
+            history_bars: 1000,
+            live_bars: 0,
+        })
+    }
+
+    pub fn with_start(mut self, start: DateTime<Utc>, start_price: f64) -> Self {
+        self.start = start;
+        self.start_price = start_price;
+        self
+    }
+
+    pub fn with_base_volume(mut self, base_volume: f64) -> Self {
+        self.base_volume = base_volume;
+        self
+    }
+
+    // Number of bars served by `fetch_history` and then by `fetch_live`.
+    pub fn with_bars(mut self, history_bars: usize, live_bars: usize) -> Self {
+        self.history_bars = history_bars;
+        self.live_bars = live_bars;
+        self
+    }
+
+    pub fn generate(&self) -> Result<Vec<Kline>> {
+        let interval: Interval = self.timeframe.parse()?;
+        let bar_seconds = interval.duration().num_seconds() as f64;
+
+        let mut generator = Generator {
+            model: &self.model,
+            rng: StdRng::seed_from_u64(self.seed),
+            dt: bar_seconds / STEPS_PER_BAR as f64 / SECONDS_PER_YEAR,
+            price: self.start_price,
+            regime: 0,
+            base_volume: self.base_volume,
+        };
+
+        let mut time = self.start;
+        let mut klines = Vec::with_capacity(self.history_bars + self.live_bars);
+
+        for _ in 0..self.history_bars + self.live_bars {
+            klines.push(generator.bar(time, &self.symbol));
+            time = interval.next(time);
+        }
+
+        Ok(klines)
+    }
+}
+
+impl Source for Synthetic {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn timeframe(&self) -> &str {
+        &self.timeframe
+    }
+
+    fn fetch_history(&self) -> Pin<Box<dyn Future<Output = Result<Vec<Kline>>> + Send + '_>> {
+        Box::pin(async move {
+            let mut klines = self.generate()?;
+            klines.truncate(self.history_bars);
+
+            Ok(klines)
+        })
+    }
+
+    fn fetch_live(&self) -> Pin<Box<dyn Stream<Item = Result<Kline>> + Send>> {
+        let live = match self.generate() {
+            Ok(mut klines) => klines.split_off(self.history_bars).into_iter().map(Ok).collect(),
+            Err(e) => vec![Err(e)],
+        };
+
+        Box::pin(stream::iter(live))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+    use futures::StreamExt;
+
+    use crate::source::Source;
+
+    use super::{Model, Regime, Synthetic};
+
+    fn gbm() -> Model {
+        Model::GeometricBrownianMotion {
+            drift: 0.0,
+            volatility: 0.8,
+        }
+    }
+
+    fn log_returns(synthetic: &Synthetic) -> Vec<f64> {
+        let klines = synthetic.generate().unwrap();
+        klines.iter().map(|k| (k.close / k.open).ln()).collect()
+    }
+
+    fn std_dev(values: &[f64]) -> f64 {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn test_same_seed_same_klines() {
+        let first = Synthetic::new("SYN".to_string(), "1m".to_string(), gbm(), 7)
+            .unwrap()
+            .generate()
+            .unwrap();
+        let second = Synthetic::new("SYN".to_string(), "1m".to_string(), gbm(), 7)
+            .unwrap()
+            .generate()
+            .unwrap();
+        let other = Synthetic::new("SYN".to_string(), "1m".to_string(), gbm(), 8)
+            .unwrap()
+            .generate()
+            .unwrap();
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_klines_are_consistent() {
+        let klines = Synthetic::new("SYN".to_string(), "5m".to_string(), gbm(), 1)
+            .unwrap()
+            .generate()
+            .unwrap();
+
+        assert_eq!(klines.len(), 1000);
+        assert!(klines
+            .windows(2)
+            .all(|w| w[1].time - w[0].time == TimeDelta::minutes(5)));
+        assert!(klines.windows(2).all(|w| w[1].open == w[0].close));
+        assert!(klines
+            .iter()
+            .all(|k| k.low <= k.open.min(k.close) && k.high >= k.open.max(k.close) && k.volume > 0.0));
+    }
+
+    #[test]
+    fn test_gbm_volatility() {
+        let synthetic = Synthetic::new("SYN".to_string(), "1h".to_string(), gbm(), 3)
+            .unwrap()
+            .with_bars(5000, 0);
+        let expected = 0.8 * (3600.0_f64 / (365.0 * 24.0 * 3600.0)).sqrt();
+
+        let measured = std_dev(&log_returns(&synthetic));
+
+        assert!((measured / expected - 1.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_ornstein_uhlenbeck_reverts_to_mean() {
+        let model = Model::OrnsteinUhlenbeck {
+            mean: 50.0,
+            reversion: 500.0,
+            volatility: 20.0,
+        };
+        let klines = Synthetic::new("SYN".to_string(), "1h".to_string(), model, 5)
+            .unwrap()
+            .with_bars(2000, 0)
+            .generate()
+            .unwrap();
+
+        let tail = &klines[1000..];
+        let mean = tail.iter().map(|k| k.close).sum::<f64>() / tail.len() as f64;
+
+        assert_eq!(klines[0].open, 100.0);
+        assert!((mean - 50.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_jump_diffusion_has_fat_tails() {
+        let model = Model::JumpDiffusion {
+            drift: 0.0,
+            volatility: 0.5,
+            jump_intensity: 200.0,
+            jump_mean: 0.0,
+            jump_volatility: 0.05,
+        };
+        let returns = log_returns(
+            &Synthetic::new("SYN".to_string(), "1h".to_string(), model, 11)
+                .unwrap()
+                .with_bars(5000, 0),
+        );
+
+        let sigma = std_dev(&returns);
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let kurtosis = returns.iter().map(|r| ((r - mean) / sigma).powi(4)).sum::<f64>() / returns.len() as f64;
+
+        assert!(kurtosis > 4.0);
+    }
+
+    #[test]
+    fn test_regime_switching_changes_volatility() {
+        let model = Model::RegimeSwitching {
+            regimes: vec![
+                Regime {
+                    drift: 0.0,
+                    volatility: 0.1,
+                },
+                Regime {
+                    drift: 0.0,
+                    volatility: 2.0,
+                },
+            ],
+            switch_probability: 0.01,
+        };
+        let returns = log_returns(
+            &Synthetic::new("SYN".to_string(), "1h".to_string(), model, 13)
+                .unwrap()
+                .with_bars(5000, 0),
+        );
+
+        let windows = returns.chunks(50).map(std_dev).collect::<Vec<f64>>();
+        let calm = windows.iter().cloned().fold(f64::MAX, f64::min);
+        let wild = windows.iter().cloned().fold(0.0, f64::max);
+
+        assert!(wild / calm > 5.0);
+    }
+
+    #[test]
+    fn test_ornstein_uhlenbeck_without_reversion() {
+        let model = Model::OrnsteinUhlenbeck {
+            mean: 50.0,
+            reversion: 0.0,
+            volatility: 20.0,
+        };
+        let klines = Synthetic::new("SYN".to_string(), "1h".to_string(), model, 5)
+            .unwrap()
+            .generate()
+            .unwrap();
+
+        assert!(klines.iter().all(|k| k.close.is_finite()));
+        assert!(klines.windows(2).any(|w| w[1].close != w[0].close));
+    }
+
+    #[test]
+    fn test_rejects_empty_regimes() {
+        let model = Model::RegimeSwitching {
+            regimes: Vec::new(),
+            switch_probability: 0.01,
+        };
+
+        assert!(Synthetic::new("SYN".to_string(), "1h".to_string(), model, 13).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_live_continues_history() {
+        let synthetic = Synthetic::new("SYN".to_string(), "1m".to_string(), gbm(), 17)
+            .unwrap()
+            .with_bars(100, 20);
+
+        let history = synthetic.fetch_history().await.unwrap();
+        let live = synthetic.fetch_live().map(|k| k.unwrap()).collect::<Vec<_>>().await;
+
+        assert_eq!(history.len(), 100);
+        assert_eq!(live.len(), 20);
+        assert_eq!(live[0].open, history[99].close);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{
+    data_structures::{
+        history::History,
+        signal::{Signal, SignalType},
+    },
+    indicators::{bollinger::BollingerParams, factor::Factor, price::PriceSource, IndicatorIdentifier},
+};
+
+use super::Strategy;
+
+const LOOK_BACK: usize = 2;
+
+#[derive(Clone)]
+pub struct BollingerReversionStrategyParams {
+    pub period: usize,
+    pub deviations: f64,
+}
+
+// Mean reversion on Bollinger Bands: buys when the close gets back inside the
+// lower band and exits once it reaches the middle band.
+pub struct BollingerReversionStrategy {
+    name: String,
+    params: BollingerReversionStrategyParams,
+}
+
+impl BollingerReversionStrategy {
+    pub fn new(name: String, params: BollingerReversionStrategyParams) -> Self {
+        Self { name, params }
+    }
+
+    fn detect_signal(&self, closes: &[f64], lower: &[f64], mid: &[f64]) -> Option<SignalType> {
+        if closes.len() < LOOK_BACK || lower.len() < LOOK_BACK || mid.len() < LOOK_BACK {
+            return None;
+        }
+
+        let (previous, current) = (closes[closes.len() - 2], closes[closes.len() - 1]);
+        let crossed_up = |band: &[f64]| previous < band[band.len() - 2] && current >= band[band.len() - 1];
+
+        if crossed_up(lower) {
+            Some(SignalType::Buy)
+        } else if crossed_up(mid) {
+            Some(SignalType::Sell)
+        } else {
+            Some(SignalType::Hold)
+        }
+    }
+
+    fn bollinger_indicator_descriptor(&self) -> IndicatorIdentifier {
+        IndicatorIdentifier::Bollinger(BollingerParams {
+            period: self.params.period,
+            deviations: Factor(self.params.deviations),
+            source: PriceSource::Close,
+        })
+    }
+}
+
+impl Strategy for BollingerReversionStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn request_indicators(&self) -> Vec<IndicatorIdentifier> {
+        vec![self.bollinger_indicator_descriptor()]
+    }
+
+    fn generate_signals(&self, history: &History) -> Vec<Signal> {
+        let mut signals = Vec::new();
+
+        let klines = history.last(LOOK_BACK);
+
+        if klines.len() < LOOK_BACK {
+            return signals;
+        }
+
+        let bollinger = self.bollinger_indicator_descriptor();
+        let closes = klines.iter().map(|kline| kline.close).collect::<Vec<f64>>();
+        let lower = history.get_indicator_output(&bollinger, "lower", LOOK_BACK);
+        let mid = history.get_indicator_output(&bollinger, "mid", LOOK_BACK);
+
+        if let Some(signal_type) = self.detect_signal(&closes, &lower, &mid) {
+            let latest_kline = &klines[klines.len() - 1];
+
+            signals.push(Signal::with_kline(signal_type, self.name.clone(), latest_kline));
+        }
+
+        signals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices, signal::SignalType},
+        strategies::Strategy,
+    };
+
+    use super::{BollingerReversionStrategy, BollingerReversionStrategyParams};
+
+    fn signals(prices: &[f64]) -> Vec<SignalType> {
+        let strategy = BollingerReversionStrategy::new(
+            "BollingerReversion".to_string(),
+            BollingerReversionStrategyParams {
+                period: 10,
+                deviations: 2.0,
+            },
+        );
+
+        let mut history = History::new();
+        history.request_calculators(strategy.request_indicators().as_slice());
+        let mut signals = Vec::new();
+
+        for kline in generate_klines_with_prices(prices) {
+            history.insert(kline);
+
+            if strategy.is_ready(&history) {
+                signals.extend(
+                    strategy
+                        .generate_signals(&history)
+                        .into_iter()
+                        .map(|signal| signal.signal_type),
+                );
+            }
+        }
+
+        signals
+    }
+
+    #[test]
+    fn test_buy_back_inside_lower_band_and_exit_at_middle() {
+        let prices = vec![
+            // slowly rising prices
+            100.0, 100.2, 100.4, 100.6, 100.8, 101.0, 101.2, 101.4, 101.6, 101.8,
+            // a drop below the lower band, a close back inside it and a recovery past the middle band
+            94.0, 97.0, 101.0, 102.0,
+        ];
+
+        assert_eq!(
+            signals(&prices),
+            vec![SignalType::Hold, SignalType::Buy, SignalType::Sell, SignalType::Hold]
+        );
+    }
+
+    #[test]
+    fn test_hold_while_falling_inside_bands() {
+        let prices = (0..15).map(|i| 100.0 - i as f64).collect::<Vec<f64>>();
+
+        let signals = signals(&prices);
+
+        assert_eq!(signals.len(), 5);
+        assert!(signals.iter().all(|signal| *signal == SignalType::Hold));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{
+    data_structures::{
+        history::History,
+        signal::{Signal, SignalType},
+    },
+    indicators::{bollinger::BollingerParams, factor::Factor, price::PriceSource, IndicatorIdentifier},
+};
+
+use super::Strategy;
+
+#[derive(Clone)]
+pub struct BollingerSqueezeStrategyParams {
+    pub period: usize,
+    pub deviations: f64,
+    // bars the bandwidth has to stay at or below `max_bandwidth` to count as a
+    // squeeze
+    pub squeeze: usize,
+    pub max_bandwidth: f64,
+}
+
+// Squeeze breakout on Bollinger Bands: once the bandwidth expands past
+// `max_bandwidth` after a squeeze, buys a close above the upper band and
+// sells one below the lower band.
+pub struct BollingerSqueezeStrategy {
+    name: String,
+    params: BollingerSqueezeStrategyParams,
+}
+
+impl BollingerSqueezeStrategy {
+    pub fn new(name: String, params: BollingerSqueezeStrategyParams) -> Self {
+        Self { name, params }
+    }
+
+    fn look_back(&self) -> usize {
+        self.params.squeeze + 1
+    }
+
+    fn detect_signal(&self, close: f64, bandwidth: &[f64], upper: f64, lower: f64) -> Option<SignalType> {
+        let (current, squeeze) = bandwidth.split_last()?;
+        if squeeze.len() < self.params.squeeze {
+            return None;
+        }
+
+        let breakout = *current > self.params.max_bandwidth
+            && squeeze.iter().all(|bandwidth| *bandwidth <= self.params.max_bandwidth);
+
+        match breakout {
+            true if close > upper => Some(SignalType::Buy),
+            true if close < lower => Some(SignalType::Sell),
+            _ => Some(SignalType::Hold),
+        }
+    }
+
+    fn bollinger_indicator_descriptor(&self) -> IndicatorIdentifier {
+        IndicatorIdentifier::Bollinger(BollingerParams {
+            period: self.params.period,
+            deviations: Factor(self.params.deviations),
+            source: PriceSource::Close,
+        })
+    }
+}
+
+impl Strategy for BollingerSqueezeStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn request_indicators(&self) -> Vec<IndicatorIdentifier> {
+        vec![self.bollinger_indicator_descriptor()]
+    }
+
+    fn generate_signals(&self, history: &History) -> Vec<Signal> {
+        let mut signals = Vec::new();
+
+        let klines = history.last(1);
+        let bollinger = self.bollinger_indicator_descriptor();
+        let bandwidth = history.get_indicator_output(&bollinger, "bandwidth", self.look_back());
+        let upper = history.get_indicator_output(&bollinger, "upper", 1);
+        let lower = history.get_indicator_output(&bollinger, "lower", 1);
+
+        if let (Some(latest_kline), Some(upper), Some(lower)) = (klines.last(), upper.last(), lower.last()) {
+            if let Some(signal_type) = self.detect_signal(latest_kline.close, &bandwidth, *upper, *lower) {
+                signals.push(Signal::with_kline(signal_type, self.name.clone(), latest_kline));
+            }
+        }
+
+        signals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices, signal::SignalType},
+        strategies::Strategy,
+    };
+
+    use super::{BollingerSqueezeStrategy, BollingerSqueezeStrategyParams};
+
+    fn signals(prices: &[f64], max_bandwidth: f64) -> Vec<SignalType> {
+        let strategy = BollingerSqueezeStrategy::new(
+            "BollingerSqueeze".to_string(),
+            BollingerSqueezeStrategyParams {
+                period: 10,
+                deviations: 2.0,
+                squeeze: 2,
+                max_bandwidth,
+            },
+        );
+
+        let mut history = History::new();
+        history.request_calculators(strategy.request_indicators().as_slice());
+        let mut signals = Vec::new();
+
+        for kline in generate_klines_with_prices(prices) {
+            history.insert(kline);
+
+            if strategy.is_ready(&history) {
+                signals.extend(
+                    strategy
+                        .generate_signals(&history)
+                        .into_iter()
+                        .map(|signal| signal.signal_type),
+                );
+            }
+        }
+
+        signals
+    }
+
+    fn squeeze_then(breakout: [f64; 2]) -> Vec<f64> {
+        // a bandwidth of 1% while the prices move in a narrow range
+        let mut prices = [100.0, 100.5].repeat(6);
+        prices.extend(breakout);
+        prices
+    }
+
+    #[test]
+    fn test_breakout_above_upper_band_after_squeeze() {
+        // the bandwidth expands to 6.9% on the breakout bar
+        assert_eq!(
+            signals(&squeeze_then([106.0, 107.0]), 0.02),
+            vec![SignalType::Hold, SignalType::Buy, SignalType::Hold]
+        );
+    }
+
+    #[test]
+    fn test_breakdown_below_lower_band_after_squeeze() {
+        assert_eq!(
+            signals(&squeeze_then([94.0, 93.0]), 0.02),
+            vec![SignalType::Hold, SignalType::Sell, SignalType::Hold]
+        );
+    }
+
+    #[test]
+    fn test_hold_without_squeeze() {
+        let signals = signals(&squeeze_then([106.0, 107.0]), 0.005);
+
+        assert!(signals.iter().all(|signal| *signal == SignalType::Hold));
+    }
+}
@@ -7,6 +7,10 @@ use super::Strategy;
 
 const LOOK_BACK: usize = 2;
 
+pub struct EMACrossoverStrategyParams {
+    pub period: usize,
+}
+
 pub struct PriceCrossOverStrategy {
     name: String,
     indicator: IndicatorIdentifier,
@@ -83,7 +87,7 @@ impl Strategy for PriceCrossOverStrategy {
 mod tests {
     use crate::{
         data_structures::{history::History, kline::helpers::generate_klines_with_prices},
-        indicators::ema::{EMAParams, EMA},
+        indicators::{ema::EMAParams, price::PriceSource},
     };
 
     use super::*;
@@ -92,7 +96,10 @@ mod tests {
     fn test_crossover_buy_signal() {
         let mut history = History::new();
 
-        let indicator = IndicatorIdentifier::EMA(EMAParams { period: 3 });
+        let indicator = IndicatorIdentifier::EMA(EMAParams {
+            period: 3,
+            source: PriceSource::Close,
+        });
         let strategy = PriceCrossOverStrategy::new("PriceCrossEMA".to_string(), indicator);
 
         history.request_calculators(strategy.request_indicators().as_slice());
@@ -113,7 +120,10 @@ mod tests {
     fn test_crossover_sell_signal() {
         let mut history = History::new();
 
-        let indicator = IndicatorIdentifier::EMA(EMAParams { period: 3 });
+        let indicator = IndicatorIdentifier::EMA(EMAParams {
+            period: 3,
+            source: PriceSource::Close,
+        });
         let strategy = PriceCrossOverStrategy::new("PriceCrossEMA".to_string(), indicator);
 
         history.request_calculators(strategy.request_indicators().as_slice());
@@ -134,7 +144,10 @@ mod tests {
     fn test_crossover_trend_up_hold_signal() {
         let mut history = History::new();
 
-        let indicator = IndicatorIdentifier::EMA(EMAParams { period: 3 });
+        let indicator = IndicatorIdentifier::EMA(EMAParams {
+            period: 3,
+            source: PriceSource::Close,
+        });
         let strategy = PriceCrossOverStrategy::new("PriceCrossEMA".to_string(), indicator);
 
         history.request_calculators(strategy.request_indicators().as_slice());
@@ -155,7 +168,10 @@ mod tests {
     fn test_crossover_trend_down_hold_signal() {
         let mut history = History::new();
 
-        let indicator = IndicatorIdentifier::EMA(EMAParams { period: 3 });
+        let indicator = IndicatorIdentifier::EMA(EMAParams {
+            period: 3,
+            source: PriceSource::Close,
+        });
         let strategy = PriceCrossOverStrategy::new("PriceCrossEMA".to_string(), indicator);
 
         history.request_calculators(strategy.request_indicators().as_slice());
@@ -0,0 +1,374 @@
+use std::cmp::Ordering;
+
+use crate::{
+    data_structures::{
+        history::History,
+        signal::{Signal, SignalType},
+    },
+    indicators::{
+        dema::DEMAParams, ema::EMAParams, hma::HMAParams, price::PriceSource, sma::SMAParams, tema::TEMAParams,
+        wma::WMAParams, IndicatorIdentifier,
+    },
+    source::Result,
+};
+
+use super::Strategy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum MovingAverage {
+    EMA,
+    SMA,
+    WMA,
+    DEMA,
+    TEMA,
+    HMA,
+}
+
+impl MovingAverage {
+    pub const ALL: [MovingAverage; 6] = [
+        MovingAverage::EMA,
+        MovingAverage::SMA,
+        MovingAverage::WMA,
+        MovingAverage::DEMA,
+        MovingAverage::TEMA,
+        MovingAverage::HMA,
+    ];
+
+    pub fn identifier(&self, period: usize) -> IndicatorIdentifier {
+        let source = PriceSource::Close;
+
+        match self {
+            MovingAverage::EMA => IndicatorIdentifier::EMA(EMAParams { period, source }),
+            MovingAverage::SMA => IndicatorIdentifier::SMA(SMAParams { period, source }),
+            MovingAverage::WMA => IndicatorIdentifier::WMA(WMAParams { period, source }),
+            MovingAverage::DEMA => IndicatorIdentifier::DEMA(DEMAParams { period, source }),
+            MovingAverage::TEMA => IndicatorIdentifier::TEMA(TEMAParams { period, source }),
+            MovingAverage::HMA => IndicatorIdentifier::HMA(HMAParams { period, source }),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DualCrossOverStrategyParams {
+    pub average: MovingAverage,
+    pub fast_period: usize,
+    pub slow_period: usize,
+    // bars the fast line has to stay beyond the slow one before signalling
+    pub confirmation: usize,
+    // distance between the lines relative to the slow one below which they
+    // count as not crossed, to ignore lines running into each other
+    pub min_spread: f64,
+}
+
+// Buys when a fast moving average crosses above a slow one and sells when it
+// crosses below, e.g. EMA 9 / EMA 21 or SMA 50 / SMA 200.
+pub struct DualCrossOverStrategy {
+    name: String,
+    fast: IndicatorIdentifier,
+    slow: IndicatorIdentifier,
+    confirmation: usize,
+    min_spread: f64,
+}
+
+impl DualCrossOverStrategy {
+    pub fn new(name: String, params: DualCrossOverStrategyParams) -> Result<Self> {
+        if params.fast_period >= params.slow_period {
+            return Err(format!(
+                "fast period {} is not shorter than the slow period {}",
+                params.fast_period, params.slow_period
+            )
+            .into());
+        }
+
+        Ok(Self::with_indicators(
+            name,
+            params.average.identifier(params.fast_period),
+            params.average.identifier(params.slow_period),
+        )
+        .with_confirmation(params.confirmation)
+        .with_min_spread(params.min_spread))
+    }
+
+    // Crossovers of any two indicators, the fast one first.
+    pub fn with_indicators(name: String, fast: IndicatorIdentifier, slow: IndicatorIdentifier) -> Self {
+        Self {
+            name,
+            fast,
+            slow,
+            confirmation: 0,
+            min_spread: 0.0,
+        }
+    }
+
+    pub fn with_confirmation(mut self, bars: usize) -> Self {
+        self.confirmation = bars;
+        self
+    }
+
+    pub fn with_min_spread(mut self, spread: f64) -> Self {
+        self.min_spread = spread;
+        self
+    }
+
+    // 1 while the fast line is above the slow one by more than the minimum
+    // spread, -1 while it is below by more and 0 in between. The spread is
+    // relative to the slow line, so against a slow line at 0, as oscillators
+    // have, only the side counts. Lines that are not numbers are on neither.
+    fn side(&self, fast: f64, slow: f64) -> i8 {
+        if slow == 0.0 {
+            return match fast.partial_cmp(&slow) {
+                Some(Ordering::Greater) => 1,
+                Some(Ordering::Less) => -1,
+                _ => 0,
+            };
+        }
+
+        let spread = (fast - slow) / slow.abs();
+
+        if spread > self.min_spread {
+            1
+        } else if spread < -self.min_spread {
+            -1
+        } else {
+            0
+        }
+    }
+
+    // The side of the lines on each of the last `count` bars both have values
+    // for, oldest first.
+    fn sides(&self, history: &History, count: usize) -> Vec<i8> {
+        let last = |indicator| {
+            history
+                .get_indicator_values(indicator, count)
+                .iter()
+                .filter_map(|value| value.last().copied())
+                .collect::<Vec<f64>>()
+        };
+        let (fast, slow) = (last(&self.fast), last(&self.slow));
+        let count = fast.len().min(slow.len());
+
+        fast[fast.len() - count..]
+            .iter()
+            .zip(&slow[slow.len() - count..])
+            .map(|(fast, slow)| self.side(*fast, *slow))
+            .collect()
+    }
+
+    // The last side the lines were on before the last `count` bars. Bars on
+    // which they ran within the minimum spread are skipped, looking further
+    // back as long as needed; 0 if they never left it.
+    fn side_before(&self, history: &History, count: usize) -> i8 {
+        let mut look_back = 2 * count;
+
+        loop {
+            let sides = self.sides(history, look_back);
+            let before = &sides[..sides.len().saturating_sub(count)];
+
+            if let Some(side) = before.iter().rev().find(|side| **side != 0) {
+                return *side;
+            }
+            if sides.len() < look_back {
+                return 0;
+            }
+
+            look_back *= 2;
+        }
+    }
+
+    // A signal once the lines crossed `confirmation` bars ago and stayed
+    // crossed since. Lines coming back out of the minimum spread on the side
+    // they were on before have not crossed.
+    fn detect_crossover(&self, history: &History) -> Option<SignalType> {
+        let count = self.confirmation + 1;
+        let sides = self.sides(history, count + 1);
+        if sides.len() <= count {
+            return None;
+        }
+
+        let side = sides[1];
+        if side == 0 || sides[1..].iter().any(|crossed| *crossed != side) {
+            return Some(SignalType::Hold);
+        }
+
+        match (side, self.side_before(history, count)) {
+            (side, before) if side == before => Some(SignalType::Hold),
+            (1, _) => Some(SignalType::Buy),
+            _ => Some(SignalType::Sell),
+        }
+    }
+}
+
+impl Strategy for DualCrossOverStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn request_indicators(&self) -> Vec<IndicatorIdentifier> {
+        vec![self.fast.clone(), self.slow.clone()]
+    }
+
+    fn generate_signals(&self, history: &History) -> Vec<Signal> {
+        let mut signals = Vec::new();
+        let klines = history.last(1);
+
+        if let (Some(kline), Some(signal_type)) = (klines.last(), self.detect_crossover(history)) {
+            signals.push(Signal::with_kline(signal_type, self.name.clone(), kline));
+        }
+
+        signals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_structures::{history::History, kline::helpers::generate_klines_with_prices, signal::SignalType},
+        strategies::Strategy,
+    };
+
+    use super::{DualCrossOverStrategy, DualCrossOverStrategyParams, MovingAverage};
+
+    fn params() -> DualCrossOverStrategyParams {
+        DualCrossOverStrategyParams {
+            average: MovingAverage::SMA,
+            fast_period: 2,
+            slow_period: 4,
+            confirmation: 0,
+            min_spread: 0.0,
+        }
+    }
+
+    // The signal of every bar once both lines cover the look back.
+    fn signals(strategy: &DualCrossOverStrategy, prices: &[f64]) -> Vec<SignalType> {
+        let mut history = History::new();
+        history.request_calculators(&strategy.request_indicators());
+        let mut signals = Vec::new();
+
+        for kline in generate_klines_with_prices(prices) {
+            history.insert(kline);
+
+            if strategy.is_ready(&history) {
+                signals.extend(
+                    strategy
+                        .generate_signals(&history)
+                        .into_iter()
+                        .map(|signal| signal.signal_type),
+                );
+            }
+        }
+
+        signals
+    }
+
+    const PRICES: [f64; 10] = [10.0, 10.0, 10.0, 10.0, 9.0, 8.0, 10.0, 12.0, 13.0, 13.0];
+
+    #[test]
+    fn test_fast_crossing_slow() {
+        let strategy = DualCrossOverStrategy::new("SMACrossOver".to_string(), params()).unwrap();
+
+        // fast: 9.5 8.5 9 11 12.5 13, slow: 9.75 9.25 9.25 9.75 10.75 12
+        assert_eq!(
+            signals(&strategy, &PRICES),
+            vec![
+                SignalType::Sell,
+                SignalType::Hold,
+                SignalType::Hold,
+                SignalType::Buy,
+                SignalType::Hold,
+                SignalType::Hold,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_confirmation_delays_signals() {
+        let strategy = DualCrossOverStrategy::new(
+            "SMACrossOver".to_string(),
+            DualCrossOverStrategyParams {
+                confirmation: 1,
+                ..params()
+            },
+        )
+        .unwrap();
+
+        let signals = signals(&strategy, &PRICES);
+
+        // the sell is confirmed on the bar after the crossing, the first one with
+        // three values of both lines
+        assert_eq!(signals[0], SignalType::Sell);
+        assert_eq!(signals[3], SignalType::Buy);
+        assert_eq!(signals.iter().filter(|signal| **signal != SignalType::Hold).count(), 2);
+    }
+
+    #[test]
+    fn test_min_spread_filters_shallow_crossings() {
+        let strategy = DualCrossOverStrategy::new(
+            "SMACrossOver".to_string(),
+            DualCrossOverStrategyParams {
+                min_spread: 0.09,
+                ..params()
+            },
+        )
+        .unwrap();
+
+        // the fast line dips at most 8.1% below the slow one, then rises 12.8% above it
+        let signals = signals(&strategy, &PRICES);
+
+        assert!(!signals.contains(&SignalType::Sell));
+        assert_eq!(signals[3], SignalType::Buy);
+    }
+
+    #[test]
+    fn test_side_of_a_slow_line_at_zero() {
+        let strategy = DualCrossOverStrategy::new("SMACrossOver".to_string(), params())
+            .unwrap()
+            .with_min_spread(0.5);
+
+        assert_eq!(strategy.side(0.1, 0.0), 1);
+        assert_eq!(strategy.side(-0.1, 0.0), -1);
+        assert_eq!(strategy.side(0.0, 0.0), 0);
+        assert_eq!(strategy.side(f64::NAN, 0.0), 0);
+        assert_eq!(strategy.side(f64::NAN, 1.0), 0);
+    }
+
+    #[test]
+    fn test_lines_within_the_spread_have_not_crossed_back() {
+        let strategy = DualCrossOverStrategy::new(
+            "SMACrossOver".to_string(),
+            DualCrossOverStrategyParams {
+                min_spread: 0.05,
+                ..params()
+            },
+        )
+        .unwrap();
+
+        // the fast line rises 11% above the slow one, runs back to within 3%
+        // of it and rises 7% above it again
+        let signals = signals(&strategy, &[10.0, 10.0, 10.0, 10.0, 12.0, 13.0, 12.0, 11.5, 13.0, 14.0]);
+
+        assert_eq!(
+            signals,
+            vec![
+                SignalType::Hold,
+                SignalType::Buy,
+                SignalType::Hold,
+                SignalType::Hold,
+                SignalType::Hold,
+                SignalType::Hold,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejects_fast_period_not_shorter_than_slow() {
+        for slow_period in [1, 2] {
+            let params = DualCrossOverStrategyParams {
+                slow_period,
+                ..params()
+            };
+
+            assert!(DualCrossOverStrategy::new("SMACrossOver".to_string(), params).is_err());
+        }
+    }
+}
@@ -1,26 +1,49 @@
-use crate::indicators::ema::EMAParams;
+use crate::{
+    indicators::{ema::EMAParams, price::PriceSource, IndicatorIdentifier},
+    source::Result,
+};
 
-use super::{crossover::PriceCrossOverStrategy, rsi_strategy::RSIStrategyParams};
-
-struct EMACrossoverStrategyParams {
-    period: usize,
-}
+use super::{
+    bollinger_reversion::{BollingerReversionStrategy, BollingerReversionStrategyParams},
+    bollinger_squeeze::{BollingerSqueezeStrategy, BollingerSqueezeStrategyParams},
+    crossover::{EMACrossoverStrategyParams, PriceCrossOverStrategy},
+    dual_crossover::{DualCrossOverStrategy, DualCrossOverStrategyParams},
+    rsi_strategy::{RSIStrategy, RSIStrategyParams},
+    Strategy,
+};
 
 pub enum StrategyIdentifier {
     EMACrossoverStrategy(EMACrossoverStrategyParams),
     RSIStrategy(RSIStrategyParams),
+    DualCrossOver(DualCrossOverStrategyParams),
+    BollingerReversion(BollingerReversionStrategyParams),
+    BollingerSqueeze(BollingerSqueezeStrategyParams),
 }
 
 pub struct Factory {}
 
 impl Factory {
-    pub fn create(strategy: &StrategyIdentifier) -> Box<dyn Strategy> {
-        match strategy {
-            StrategyIdentifier::EMACrossoverStrategy(params) => PriceCrossOverStrategy::new(
+    pub fn create(strategy: &StrategyIdentifier) -> Result<Box<dyn Strategy>> {
+        Ok(match strategy {
+            StrategyIdentifier::EMACrossoverStrategy(params) => Box::new(PriceCrossOverStrategy::new(
                 "EmaCrossover".to_string(),
-                crate::indicators::IndicatorIdentifier::EMA(EMAParams { period: params.period }),
-            ),
-            StrategyIdentifier::RSIStrategy(params) => RSIStrategyParams::new("RSI".to_string(), params),
-        }
+                IndicatorIdentifier::EMA(EMAParams {
+                    period: params.period,
+                    source: PriceSource::Close,
+                }),
+            )),
+            StrategyIdentifier::RSIStrategy(params) => Box::new(RSIStrategy::new("RSI".to_string(), params.clone())),
+            StrategyIdentifier::DualCrossOver(params) => {
+                Box::new(DualCrossOverStrategy::new("DualCrossover".to_string(), params.clone())?)
+            }
+            StrategyIdentifier::BollingerReversion(params) => Box::new(BollingerReversionStrategy::new(
+                "BollingerReversion".to_string(),
+                params.clone(),
+            )),
+            StrategyIdentifier::BollingerSqueeze(params) => Box::new(BollingerSqueezeStrategy::new(
+                "BollingerSqueeze".to_string(),
+                params.clone(),
+            )),
+        })
     }
 }
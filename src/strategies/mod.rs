@@ -1,13 +1,51 @@
 use crate::{
-    data_structures::{history::History, signal::Signal},
+    data_structures::{history::History, interval::Interval, signal::Signal, universe::Universe},
     indicators::IndicatorIdentifier,
 };
 
+pub mod bollinger_reversion;
+pub mod bollinger_squeeze;
 pub mod crossover;
+pub mod dual_crossover;
+pub mod factory;
 pub mod rsi_strategy;
 
 pub trait Strategy {
     fn name(&self) -> &str;
     fn request_indicators(&self) -> Vec<IndicatorIdentifier>;
+
+    // Higher timeframes the strategy reads through `History::timeframe`, with
+    // the indicators to calculate on each of them.
+    fn request_timeframes(&self) -> Vec<(Interval, Vec<IndicatorIdentifier>)> {
+        Vec::new()
+    }
+
     fn generate_signals(&self, history: &History) -> Vec<Signal>;
+
+    // Whether every requested indicator has warmed up; the processors do not
+    // evaluate a strategy before.
+    fn is_ready(&self, history: &History) -> bool {
+        history.is_ready(&self.request_indicators())
+            && self.request_timeframes().iter().all(|(interval, indicators)| {
+                history
+                    .timeframe(*interval)
+                    .is_some_and(|timeframe| timeframe.is_ready(indicators))
+            })
+    }
+}
+
+// A strategy looking at several symbols at once, evaluated once per bar after
+// every symbol received it.
+pub trait PortfolioStrategy {
+    fn name(&self) -> &str;
+    fn request_indicators(&self) -> Vec<(String, IndicatorIdentifier)>;
+    fn generate_signals(&self, universe: &Universe) -> Vec<Signal>;
+
+    fn is_ready(&self, universe: &Universe) -> bool {
+        self.request_indicators().iter().all(|(symbol, indicator)| {
+            universe
+                .history(symbol)
+                .is_some_and(|history| history.is_ready(std::slice::from_ref(indicator)))
+        })
+    }
 }
@@ -10,6 +10,7 @@ use super::Strategy;
 
 const LOOK_BACK: usize = 2;
 
+#[derive(Clone)]
 pub struct RSIStrategyParams {
     pub period: usize,
     pub oversold_level: f64,
@@ -86,7 +87,6 @@ impl Strategy for RSIStrategy {
 mod tests {
     use crate::{
         data_structures::{history::History, kline::helpers::generate_klines_with_prices, signal::SignalType},
-        indicators::rsi::{RSIParams, RSI},
         strategies::{rsi_strategy::RSIStrategyParams, Strategy},
     };
 